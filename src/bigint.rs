@@ -0,0 +1,380 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign},
+    str::FromStr,
+};
+
+/// Every limb holds 9 decimal digits, so printing needs no division
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// Arbitrary size unsigned integer, for the answers that overflow a `u128`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Least significant first, no trailing zero limbs so 0 has none
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    /// Number written by `digits` in base 10, most significant first. Panics on a
+    /// digit bigger than 9.
+    pub fn from_digits(digits: impl IntoIterator<Item = u32>) -> Self {
+        let mut n = Self::zero();
+        for d in digits {
+            assert!(d < 10, "{d} is not a decimal digit");
+            n.mul_small(10);
+            n.add_small(d);
+        }
+        n
+    }
+
+    pub fn mul_small(&mut self, m: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.limbs {
+            let v = *limb as u64 * m as u64 + carry;
+            *limb = (v % BASE) as u32;
+            carry = v / BASE;
+        }
+        while carry > 0 {
+            self.limbs.push((carry % BASE) as u32);
+            carry /= BASE;
+        }
+        self.trim();
+    }
+
+    pub fn add_small(&mut self, a: u32) {
+        let mut carry = a as u64;
+        for limb in &mut self.limbs {
+            if carry == 0 {
+                return;
+            }
+            let v = *limb as u64 + carry;
+            *limb = (v % BASE) as u32;
+            carry = v / BASE;
+        }
+        while carry > 0 {
+            self.limbs.push((carry % BASE) as u32);
+            carry /= BASE;
+        }
+    }
+
+    /// Remainder of the division by `m`, panics if `m` is 0
+    pub fn rem_small(&self, m: u32) -> u32 {
+        assert!(m > 0, "Division by zero");
+        self.limbs
+            .iter()
+            .rev()
+            .fold(0u64, |rem, limb| (rem * BASE + *limb as u64) % m as u64) as u32
+    }
+
+    /// `None` if `rhs` is bigger
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if self < rhs {
+            return None;
+        }
+        let mut n = self.clone();
+        let mut borrow = 0u64;
+        for (i, limb) in n.limbs.iter_mut().enumerate() {
+            let sub = *rhs.limbs.get(i).unwrap_or(&0) as u64 + borrow;
+            borrow = (sub > *limb as u64) as u64;
+            *limb = (*limb as u64 + borrow * BASE - sub) as u32;
+        }
+        n.trim();
+        Some(n)
+    }
+
+    /// Quotient and remainder, panics if `rhs` is 0.
+    ///
+    /// Long division one limb at a time, every quotient limb found by binary search.
+    pub fn div_rem(&self, rhs: &BigUint) -> (BigUint, BigUint) {
+        assert!(!rhs.is_zero(), "Division by zero");
+        let mut quotient = BigUint {
+            limbs: vec![0; self.limbs.len()],
+        };
+        let mut rem = BigUint::zero();
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            // Smaller than rhs * BASE, so the quotient limb fits
+            rem.limbs.insert(0, *limb);
+            rem.trim();
+            let times = |q: u32| {
+                let mut product = rhs.clone();
+                product.mul_small(q);
+                product
+            };
+            let (mut lo, mut hi) = (0u32, (BASE - 1) as u32);
+            while lo < hi {
+                let mid = lo + (hi - lo).div_ceil(2);
+                if times(mid) <= rem {
+                    lo = mid;
+                } else {
+                    hi = mid - 1;
+                }
+            }
+            rem = rem.checked_sub(&times(lo)).unwrap();
+            quotient.limbs[i] = lo;
+        }
+        quotient.trim();
+        (quotient, rem)
+    }
+
+    /// `None` if it does not fit
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |acc, limb| {
+            acc.checked_mul(BASE as u128)?.checked_add(*limb as u128)
+        })
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = vec![];
+        while value > 0 {
+            limbs.push((value % BASE as u128) as u32);
+            value /= BASE as u128;
+        }
+        Self { limbs }
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    Self::from(value as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let v = *limb as u64 + *rhs.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            *limb = (v % BASE) as u32;
+            carry = v / BASE;
+            if carry == 0 && i >= rhs.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, rhs: &BigUint) -> BigUint {
+        self += rhs;
+        self
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: BigUint) -> BigUint {
+        self + &rhs
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        let mut acc = vec![0u64; self.limbs.len() + rhs.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in rhs.limbs.iter().enumerate() {
+                let v = acc[i + j] + *a as u64 * *b as u64 + carry;
+                acc[i + j] = v % BASE;
+                carry = v / BASE;
+            }
+            acc[i + rhs.limbs.len()] += carry;
+        }
+        let mut n = BigUint {
+            limbs: acc.into_iter().map(|limb| limb as u32).collect(),
+        };
+        n.trim();
+        n
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: BigUint) -> BigUint {
+        &self * &rhs
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, n| acc + &n)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::from(1u8), |acc, n| &acc * &n)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((most, rest)) = self.limbs.split_last() else {
+            return f.pad("0");
+        };
+        let mut s = most.to_string();
+        for limb in rest.iter().rev() {
+            s.push_str(&format!("{limb:0BASE_DIGITS$}"));
+        }
+        f.pad(&s)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Empty str cannot be a number".to_string());
+        }
+        s.chars()
+            .map(|c| c.to_digit(10).ok_or(format!("{c} is not a digit in [{s}]")))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::from_digits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        for n in [0u128, 7, 999_999_999, 1_000_000_000, u128::MAX] {
+            let big = BigUint::from(n);
+            assert_eq!(big.to_u128(), Some(n));
+            assert_eq!(big.to_string(), n.to_string());
+            assert_eq!(n.to_string().parse::<BigUint>(), Ok(big));
+        }
+        let big: BigUint = "1000000000000000000000000000000000000000007"
+            .parse()
+            .unwrap();
+        assert_eq!(big.to_u128(), None);
+        assert_eq!(big.rem_small(10), 7);
+        assert!("12a".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigUint::from(u128::MAX);
+        let b = BigUint::from(u64::MAX);
+        assert_eq!(
+            (&a * &b).to_string(),
+            "6277101735386680763495507056286727952620534092958556749825"
+        );
+        assert_eq!(
+            (a.clone() + &a).to_string(),
+            "680564733841876926926749214863536422910"
+        );
+        assert!(a > b && b > BigUint::zero());
+        assert_eq!(
+            [1u32, 2, 3].into_iter().map(BigUint::from).sum::<BigUint>(),
+            BigUint::from(6u8)
+        );
+        assert_eq!(
+            [4u32, 5, 0]
+                .into_iter()
+                .map(BigUint::from)
+                .product::<BigUint>(),
+            BigUint::zero()
+        );
+    }
+
+    #[test]
+    fn test_sub_and_div() {
+        let values = [
+            0u128,
+            1,
+            7,
+            999_999_999,
+            1_000_000_000,
+            123_456_789_012_345_678,
+            u128::MAX,
+        ];
+        for a in values {
+            for b in values {
+                let (big_a, big_b) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!(
+                    big_a.checked_sub(&big_b),
+                    a.checked_sub(b).map(BigUint::from)
+                );
+                if let Some(q) = a.checked_div(b) {
+                    let expected = (BigUint::from(q), BigUint::from(a % b));
+                    assert_eq!(big_a.div_rem(&big_b), expected);
+                }
+            }
+        }
+
+        let a = &BigUint::from(u128::MAX) * &BigUint::from(u128::MAX);
+        let b = BigUint::from(u64::MAX);
+        let (q, r) = (a.clone() + &BigUint::from(5u8)).div_rem(&b);
+        assert_eq!(&q * &b + &r, a.clone() + &BigUint::from(5u8));
+        assert!(r < b);
+        assert_eq!(
+            a.div_rem(&BigUint::from(u128::MAX)).0,
+            BigUint::from(u128::MAX)
+        );
+    }
+}
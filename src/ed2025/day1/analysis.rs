@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+
+use crate::ed2025::day1::{
+    Dial, DynCount, Movement,
+    simulator::{CountingPolicy, Simulator, Step},
+};
+
+/// Count `policy` gives to `movements` on a dial of `size` starting at `start`, and
+/// where the dial ends.
+fn simulate(
+    movements: &[Movement],
+    size: usize,
+    start: usize,
+    policy: &impl CountingPolicy,
+) -> (usize, usize) {
+    let mut sim = Simulator::new(DynCount::new(size, start));
+    let count = sim.count(movements.iter().copied(), policy);
+    (count, sim.dial().position())
+}
+
+/// Every starting position of a dial of `size` for which `movements` end with
+/// exactly `target` counted by `policy`, in increasing order.
+pub fn starting_positions(
+    movements: &[Movement],
+    size: usize,
+    target: usize,
+    policy: &impl CountingPolicy,
+) -> Vec<usize> {
+    (0..size)
+        .filter(|start| simulate(movements, size, *start, policy).0 == target)
+        .collect()
+}
+
+/// Fewest movements to add after `movements` so `policy` counts exactly `target`,
+/// `None` if the count is already past it or it can't be reached.
+///
+/// Breadth first search over (position, counts left) states, O(size² * (target - count))
+/// whatever the target. Each added movement is at most `size` clicks long, which reaches
+/// any position and the next zero. Longer ones are not tried, so a policy counting every
+/// click could reach the target in fewer movements going around several times.
+pub fn minimal_suffix(
+    movements: &[Movement],
+    size: usize,
+    start: usize,
+    target: usize,
+    policy: &impl CountingPolicy,
+) -> Option<Vec<Movement>> {
+    let (count, position) = simulate(movements, size, start, policy);
+    if count > target {
+        return None;
+    }
+
+    let state = |position: usize, count: usize| (target - count) * size + position;
+    // Previous state and the movement that got us here
+    let mut came_from: Vec<Option<(usize, Movement)>> = vec![None; size * (target - count + 1)];
+    let first = state(position, count);
+    let mut to_visit = VecDeque::from([(position, count)]);
+
+    while let Some((position, count)) = to_visit.pop_front() {
+        let current = state(position, count);
+        if count == target {
+            let mut suffix = vec![];
+            let mut at = current;
+            while at != first {
+                let (prev, movement) = came_from[at].unwrap();
+                suffix.push(movement);
+                at = prev;
+            }
+            suffix.reverse();
+            return Some(suffix);
+        }
+
+        for distance in 1..=size {
+            for movement in [Movement::Left(distance), Movement::Right(distance)] {
+                let step = Step::new(&mut DynCount::new(size, position), movement);
+                let next_count = count + policy.count(&step);
+                if next_count > target {
+                    continue;
+                }
+                let next = state(step.after, next_count);
+                if next != first && came_from[next].is_none() {
+                    came_from[next] = Some((current, movement));
+                    to_visit.push_back((step.after, next_count));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day1::simulator::{AnyZeroClick, LandedOnZero};
+
+    fn example() -> Vec<Movement> {
+        "L68 L30 R48 L5 R60 L55 L1 L99 R14 L82"
+            .split(' ')
+            .map(|m| m.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_starting_positions() {
+        let movements = example();
+        assert!(starting_positions(&movements, 100, 3, &LandedOnZero).contains(&50));
+        assert!(starting_positions(&movements, 100, 6, &AnyZeroClick).contains(&50));
+        for start in starting_positions(&movements, 100, 1, &LandedOnZero) {
+            assert_eq!(simulate(&movements, 100, start, &LandedOnZero).0, 1);
+        }
+        assert!(starting_positions(&movements, 100, 11, &LandedOnZero).is_empty());
+    }
+
+    #[test]
+    fn test_minimal_suffix() {
+        let movements = example();
+        // Ends at 32
+        assert_eq!(
+            minimal_suffix(&movements, 100, 50, 3, &LandedOnZero),
+            Some(vec![])
+        );
+        assert_eq!(
+            minimal_suffix(&movements, 100, 50, 4, &LandedOnZero),
+            Some(vec![Movement::Left(32)])
+        );
+        assert_eq!(
+            minimal_suffix(&movements, 100, 50, 6, &LandedOnZero),
+            Some(vec![
+                Movement::Left(32),
+                Movement::Left(100),
+                Movement::Left(100)
+            ])
+        );
+        assert_eq!(
+            minimal_suffix(&movements, 100, 50, 9, &AnyZeroClick),
+            Some(vec![
+                Movement::Left(32),
+                Movement::Left(100),
+                Movement::Left(100)
+            ])
+        );
+        // Only the gap to the target is searched
+        let far = [Movement::Right(100_000_000)];
+        assert_eq!(
+            minimal_suffix(&far, 100, 50, 1_000_001, &AnyZeroClick),
+            Some(vec![Movement::Left(50)])
+        );
+        assert_eq!(minimal_suffix(&movements, 100, 50, 2, &LandedOnZero), None);
+    }
+}
@@ -1,57 +1,207 @@
-use std::str::FromStr;
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
 
+use crate::parse::{Cursor, ParseError};
+
+pub mod analysis;
+pub mod simulator;
+pub mod solution;
 pub mod solution_part1;
 pub mod solution_part2;
+pub mod trace;
 
 pub const MODULE: usize = 100;
 
-#[derive(PartialEq, Debug)]
+/// A rotation of the dial, written `L68`/`R48` or signed as `-68`/`+48`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Movement {
     Left(usize),
     Right(usize),
 }
 
+impl Movement {
+    /// Clicks towards higher numbers, negative when turning left. `None` if they
+    /// don't fit in an `isize`.
+    pub fn signed(&self) -> Option<isize> {
+        match *self {
+            Self::Left(n) => isize::try_from(n).ok().map(|n| -n),
+            Self::Right(n) => isize::try_from(n).ok(),
+        }
+    }
+
+    pub fn from_signed(clicks: isize) -> Self {
+        if clicks < 0 {
+            Self::Left(clicks.unsigned_abs())
+        } else {
+            Self::Right(clicks as usize)
+        }
+    }
+}
+
+/// `L68`, or `-68` with the alternate flag (`{:#}`)
+impl Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (f.alternate(), self) {
+            (false, Self::Left(n)) => write!(f, "L{n}"),
+            (false, Self::Right(n)) => write!(f, "R{n}"),
+            (true, Self::Left(n)) => write!(f, "-{n}"),
+            (true, Self::Right(n)) => write!(f, "+{n}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MovementParseError {
+    Empty,
+    /// Leading char other than `L`, `R`, `+` or `-`
+    InvalidDirection(char),
+    InvalidDistance(ParseError),
+}
+
+impl Display for MovementParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty str cannot be a Movement"),
+            Self::InvalidDirection(c) => write!(
+                f,
+                "Non leaded by R, L, + or - str cannot be a Movement, found '{c}'"
+            ),
+            Self::InvalidDistance(e) => write!(f, "Error found when parsing distance: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MovementParseError {}
+
+impl From<MovementParseError> for String {
+    fn from(e: MovementParseError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Accepts `L68`, `R48`, signed `-68`, `+48` and combined `R-68`, `L-48`
 impl FromStr for Movement {
-    type Err = String;
+    type Err = MovementParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.chars().next() {
-            Some(movement) => match movement {
-                'L' | 'R' => {
-                    let num = match s.strip_prefix(movement).map(|n| n.parse::<usize>()) {
-                        Some(num) => {
-                            num.map_err(|e| format!("Error found when parsing distance: {e}"))?
-                        }
-                        None => Err("Invalid str after stripping L or R prefix".to_string())?,
-                    };
-                    match movement {
-                        'L' => Ok(Self::Left(num)),
-                        'R' => Ok(Self::Right(num)),
-                        _ => panic!(),
-                    }
+        let mut cursor = Cursor::new(s);
+        let mut turns_left = match cursor.peek() {
+            Some('L') => true,
+            Some('R' | '+') => false,
+            Some('-') => true,
+            Some(c) => return Err(MovementParseError::InvalidDirection(c)),
+            None => return Err(MovementParseError::Empty),
+        };
+        // Only a letter can be followed by a sign
+        if cursor.eat('L') || cursor.eat('R') {
+            if cursor.eat('-') {
+                turns_left = !turns_left;
+            } else {
+                cursor.eat('+');
+            }
+        } else {
+            cursor.next_char();
+        }
+
+        let clicks: usize = cursor.uint().map_err(MovementParseError::InvalidDistance)?;
+        cursor
+            .finish()
+            .map_err(MovementParseError::InvalidDistance)?;
+
+        Ok(match turns_left {
+            true => Self::Left(clicks),
+            false => Self::Right(clicks),
+        })
+    }
+}
+
+/// Position the dial points at before the first movement
+pub const START: usize = 50;
+
+/// A safe dial numbered `0..size()`, left turns go towards lower numbers.
+pub trait Dial {
+    fn size(&self) -> usize;
+
+    fn position(&self) -> usize;
+
+    fn set_position(&mut self, position: usize);
+
+    /// Returns the times the dial went past zero, without counting where it stops.
+    /// Every full turn counts, so `L100` from 0 on a 100 dial is 1, unlike
+    /// [`simulator::Step::passed_zero`].
+    fn move_left(&mut self, movement: usize) -> usize {
+        let (size, position) = (self.size(), self.position());
+        let extra_spins = movement / size;
+        let movement = movement % size;
+
+        if position >= movement {
+            self.set_position(position - movement);
+            extra_spins
+        } else {
+            let new_position = size - (movement - position);
+            self.set_position(new_position);
+            extra_spins
+                + if new_position != 0 && position != 0 {
+                    1
+                } else {
+                    0
                 }
-                _ => Err("Non leaded by R or L str cannot be a Movement".to_string()),
-            },
-            None => Err("Empty str cannot be a Movement".to_string()),
         }
     }
+
+    /// Returns the times the dial went past zero, without counting where it stops.
+    /// Every full turn counts, as in [`Dial::move_left`].
+    fn move_right(&mut self, movement: usize) -> usize {
+        let (size, position) = (self.size(), self.position());
+        let extra_spins = movement / size;
+        let movement = movement % size;
+
+        if position + movement >= size {
+            let new_position = position + movement - size;
+            self.set_position(new_position);
+            extra_spins + if new_position != 0 { 1 } else { 0 }
+        } else {
+            self.set_position(position + movement);
+            extra_spins
+        }
+    }
+
+    fn apply(&mut self, movement: &Movement) -> usize {
+        match *movement {
+            Movement::Left(n) => self.move_left(n),
+            Movement::Right(n) => self.move_right(n),
+        }
+    }
+}
+
+impl<D: Dial + ?Sized> Dial for &mut D {
+    fn size(&self) -> usize {
+        (**self).size()
+    }
+
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    fn set_position(&mut self, position: usize) {
+        (**self).set_position(position)
+    }
 }
 
+/// Dial whose size is known at compile time
 pub struct Count<const MODULE: usize>(usize);
 
 impl<const M: usize> From<usize> for Count<M> {
     fn from(value: usize) -> Self {
-        Self(value % MODULE)
+        Self(value % M)
     }
 }
 
 impl<const M: usize> From<i32> for Count<M> {
     fn from(value: i32) -> Self {
-        if value < 0 {
-            Self(MODULE - (value % MODULE as i32).unsigned_abs() as usize)
-        } else {
-            Self(value as usize % MODULE)
-        }
+        Self(value.rem_euclid(M as i32) as usize)
     }
 }
 
@@ -59,36 +209,51 @@ impl<const M: usize> Count<M> {
     pub fn get_inner(&self) -> usize {
         self.0
     }
+}
 
-    pub fn move_left(&mut self, movement: usize) -> usize {
-        let Count(inner) = self;
-        let extra_spins = movement / M;
-        let starts_at_zero = *inner == 0;
+impl<const M: usize> Dial for Count<M> {
+    fn size(&self) -> usize {
+        M
+    }
 
-        let movement = movement % M;
+    fn position(&self) -> usize {
+        self.0
+    }
 
-        if *inner >= movement {
-            *inner -= movement;
-            extra_spins
-        } else {
-            *inner = M - (movement - *inner);
-            extra_spins + if *inner != 0 && !starts_at_zero { 1 } else { 0 }
+    fn set_position(&mut self, position: usize) {
+        self.0 = position;
+    }
+}
+
+/// Dial whose size is only known at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynCount {
+    size: usize,
+    position: usize,
+}
+
+impl DynCount {
+    /// Panics if `size` is 0
+    pub fn new(size: usize, position: usize) -> Self {
+        assert!(size > 0, "A dial needs at least one position");
+        Self {
+            size,
+            position: position % size,
         }
     }
+}
 
-    pub fn move_right(&mut self, movement: usize) -> usize {
-        let Count(inner) = self;
-        let extra_spins = movement / M;
+impl Dial for DynCount {
+    fn size(&self) -> usize {
+        self.size
+    }
 
-        let movement = movement % M;
+    fn position(&self) -> usize {
+        self.position
+    }
 
-        if *inner + movement >= M {
-            *inner = *inner + movement - M;
-            extra_spins + if *inner != 0 { 1 } else { 0 }
-        } else {
-            *inner += movement;
-            extra_spins
-        }
+    fn set_position(&mut self, position: usize) {
+        self.position = position;
     }
 }
 
@@ -115,4 +280,84 @@ mod test {
         let movement = "L37";
         assert_eq!(Movement::from_str(movement).unwrap(), Movement::Left(37));
     }
+
+    #[test]
+    fn test_movement_notations() {
+        assert_eq!(Movement::from_str("-68"), Ok(Movement::Left(68)));
+        assert_eq!(Movement::from_str("+48"), Ok(Movement::Right(48)));
+        assert_eq!(Movement::from_str("R-5"), Ok(Movement::Left(5)));
+        assert_eq!(Movement::from_str("L-5"), Ok(Movement::Right(5)));
+        assert_eq!(Movement::from_str("L+5"), Ok(Movement::Left(5)));
+
+        for movement in [
+            Movement::Left(68),
+            Movement::Right(48),
+            Movement::Right(0),
+            Movement::Left(0),
+        ] {
+            assert_eq!(Movement::from_str(&movement.to_string()), Ok(movement));
+            assert_eq!(Movement::from_str(&format!("{movement:#}")), Ok(movement));
+        }
+        assert_eq!(
+            Movement::from_signed(Movement::Left(68).signed().unwrap()),
+            Movement::Left(68)
+        );
+
+        assert!(Movement::from_str("LR5").is_err());
+        assert!(Movement::from_str("+-5").is_err());
+        let far = format!("R{}", usize::MAX);
+        assert_eq!(Movement::from_str(&far), Ok(Movement::Right(usize::MAX)));
+        assert_eq!(Movement::Right(usize::MAX).signed(), None);
+        assert_eq!(Movement::Left(68).to_string(), "L68");
+        assert_eq!(format!("{:#}", Movement::Left(68)), "-68");
+
+        assert_eq!(Movement::from_str(""), Err(MovementParseError::Empty));
+        assert_eq!(
+            Movement::from_str("U5"),
+            Err(MovementParseError::InvalidDirection('U'))
+        );
+        let Err(MovementParseError::InvalidDistance(e)) = Movement::from_str("R5x") else {
+            panic!("R5x is not a movement");
+        };
+        assert_eq!(e.column, 3);
+    }
+
+    #[test]
+    fn test_dial_size() {
+        assert_eq!(Count::<10>::from(57usize).get_inner(), 7);
+        assert_eq!(Count::<10>::from(-3i32).get_inner(), 7);
+        assert_eq!(Count::<100>::from(-100i32).get_inner(), 0);
+
+        let mut count = Count::<10>::from(5usize);
+        let mut dyn_count = DynCount::new(10, 5);
+        for movement in [
+            Movement::Left(5),
+            Movement::Right(23),
+            Movement::Left(14),
+            Movement::Left(1),
+        ] {
+            assert_eq!(count.apply(&movement), dyn_count.apply(&movement));
+            assert_eq!(count.position(), dyn_count.position());
+        }
+        assert_eq!(count.get_inner(), 8);
+    }
+
+    #[test]
+    fn test_full_turns_from_zero() {
+        for (movement, turns) in [
+            (Movement::Left(100), 1),
+            (Movement::Right(100), 1),
+            (Movement::Left(200), 2),
+            (Movement::Right(300), 3),
+        ] {
+            let mut count = Count::<100>::from(0usize);
+            assert_eq!(count.apply(&movement), turns, "{movement}");
+            assert_eq!(count.get_inner(), 0);
+
+            // A step tells the last turn apart as the one landing on zero
+            let step = simulator::Step::new(&mut Count::<100>::from(0usize), movement);
+            assert_eq!((step.passed_zero, step.landed_on_zero), (turns - 1, true));
+            assert_eq!(step.zero_clicks(), turns);
+        }
+    }
 }
@@ -0,0 +1,201 @@
+use std::{io, str::FromStr};
+
+use crate::{
+    ed2025::day1::{Dial, Movement},
+    input::{LineSource, invalid_data},
+};
+
+/// Everything that happened to the dial during a single movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub movement: Movement,
+    pub before: usize,
+    pub after: usize,
+    /// Times the dial pointed at zero before stopping
+    pub passed_zero: usize,
+    /// The dial stopped pointing at zero
+    pub landed_on_zero: bool,
+    /// Complete turns of the dial
+    pub full_revolutions: usize,
+}
+
+impl Step {
+    /// Moves `dial` and records what happened
+    pub fn new(dial: &mut (impl Dial + ?Sized), movement: Movement) -> Self {
+        let (size, before) = (dial.size(), dial.position());
+        let (distance, after, zero_clicks) = match movement {
+            Movement::Left(n) => {
+                let zero_clicks = if before == 0 {
+                    n / size
+                } else if n >= before {
+                    (n - before) / size + 1
+                } else {
+                    0
+                };
+                (n, (before + size - n % size) % size, zero_clicks)
+            }
+            // Full turns apart so `before + n` can't overflow
+            Movement::Right(n) => (
+                n,
+                (before + n % size) % size,
+                n / size + (before + n % size) / size,
+            ),
+        };
+        dial.set_position(after);
+
+        // Staying on zero with a movement of 0 still counts as landing on it
+        let landed_on_zero = after == 0;
+        Self {
+            movement,
+            before,
+            after,
+            passed_zero: zero_clicks - (landed_on_zero && distance > 0) as usize,
+            landed_on_zero,
+            full_revolutions: distance / size,
+        }
+    }
+
+    /// Times the dial pointed at zero, including where it stopped
+    pub fn zero_clicks(&self) -> usize {
+        self.passed_zero + self.landed_on_zero as usize
+    }
+}
+
+/// Decides how much a [`Step`] adds to the final count.
+pub trait CountingPolicy {
+    fn count(&self, step: &Step) -> usize;
+}
+
+impl<F: Fn(&Step) -> usize> CountingPolicy for F {
+    fn count(&self, step: &Step) -> usize {
+        self(step)
+    }
+}
+
+/// Movements that leave the dial pointing at zero, part 1
+pub struct LandedOnZero;
+
+impl CountingPolicy for LandedOnZero {
+    fn count(&self, step: &Step) -> usize {
+        step.landed_on_zero as usize
+    }
+}
+
+/// Every click pointing at zero, part 2
+pub struct AnyZeroClick;
+
+impl CountingPolicy for AnyZeroClick {
+    fn count(&self, step: &Step) -> usize {
+        step.zero_clicks()
+    }
+}
+
+pub struct Simulator<D> {
+    dial: D,
+}
+
+impl<D: Dial> Simulator<D> {
+    pub fn new(dial: D) -> Self {
+        Self { dial }
+    }
+
+    pub fn dial(&self) -> &D {
+        &self.dial
+    }
+
+    pub fn into_dial(self) -> D {
+        self.dial
+    }
+
+    pub fn step(&mut self, movement: Movement) -> Step {
+        Step::new(&mut self.dial, movement)
+    }
+
+    /// Lazily applies every movement, the dial only moves as the steps are consumed
+    pub fn run<I: IntoIterator<Item = Movement>>(
+        &mut self,
+        movements: I,
+    ) -> impl Iterator<Item = Step> + use<'_, D, I> {
+        movements.into_iter().map(|m| self.step(m))
+    }
+
+    pub fn count(
+        &mut self,
+        movements: impl IntoIterator<Item = Movement>,
+        policy: &impl CountingPolicy,
+    ) -> usize {
+        self.run(movements).map(|step| policy.count(&step)).sum()
+    }
+
+    /// Same as [`Self::count`] reading a movement per line, a malformed movement ends
+    /// the count with an [`io::ErrorKind::InvalidData`] error.
+    pub fn count_streaming(
+        &mut self,
+        source: impl LineSource,
+        policy: &impl CountingPolicy,
+    ) -> io::Result<usize> {
+        let mut count = 0;
+        source.try_for_each_line(|_, line| {
+            let movement = Movement::from_str(line).map_err(invalid_data)?;
+            count += policy.count(&self.step(movement));
+            Ok(())
+        })?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day1::{Count, DynCount, START};
+
+    #[test]
+    fn test_step_events() {
+        let mut sim = Simulator::new(Count::<100>::from(START));
+        let step = sim.step(Movement::Right(250));
+        assert_eq!((step.before, step.after), (50, 0));
+        assert_eq!(step.passed_zero, 2);
+        assert!(step.landed_on_zero);
+        assert_eq!(step.full_revolutions, 2);
+
+        // Starting at zero does not count as passing it
+        let step = sim.step(Movement::Left(100));
+        assert_eq!((step.passed_zero, step.landed_on_zero), (0, true));
+        let step = sim.step(Movement::Right(5));
+        assert_eq!(
+            (step.passed_zero, step.landed_on_zero, step.after),
+            (0, false, 5)
+        );
+        let step = sim.step(Movement::Left(6));
+        assert_eq!((step.zero_clicks(), step.after), (1, 99));
+
+        let step = Step::new(&mut Count::<100>::from(START), Movement::Right(usize::MAX));
+        // 15 past the last full turn, from 50
+        assert_eq!(step.after, 65);
+        assert_eq!(step.zero_clicks(), usize::MAX / 100);
+        let step = Step::new(
+            &mut Count::<100>::from(90usize),
+            Movement::Right(usize::MAX),
+        );
+        assert_eq!((step.after, step.zero_clicks()), (5, usize::MAX / 100 + 1));
+    }
+
+    #[test]
+    fn test_policies() {
+        let movements: Vec<Movement> = "L68 L30 R48 L5 R60 L55 L1 L99 R14 L82"
+            .split(' ')
+            .map(|m| m.parse().unwrap())
+            .collect();
+        let count = |policy: &dyn CountingPolicy| {
+            let mut sim = Simulator::new(DynCount::new(100, START));
+            sim.run(movements.iter().copied())
+                .map(|step| policy.count(&step))
+                .sum::<usize>()
+        };
+
+        assert_eq!(count(&LandedOnZero), 3);
+        assert_eq!(count(&AnyZeroClick), 6);
+        assert_eq!(count(&|s: &Step| s.passed_zero), 3);
+        assert_eq!(count(&|s: &Step| s.full_revolutions), 0);
+    }
+}
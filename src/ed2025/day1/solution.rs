@@ -0,0 +1,40 @@
+use std::{io, str::FromStr};
+
+use crate::{
+    ed2025::day1::{
+        Count, Dial, MODULE, Movement, START,
+        simulator::{CountingPolicy, Simulator},
+    },
+    input::{self, LineSource},
+};
+
+/// Movements of `lines` applied to the puzzle dial, every step counted by `policy`
+pub fn count_zero_times<S: AsRef<str>, const MODULE: usize>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    policy: &impl CountingPolicy,
+) -> usize {
+    count_zero_times_with(lines, &mut Count::<MODULE>::from(START), policy)
+}
+
+/// Same as [`count_zero_times`] on any dial, whatever its size and starting position
+pub fn count_zero_times_with<S: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    dial: &mut impl Dial,
+    policy: &impl CountingPolicy,
+) -> usize {
+    let movements = lines.map(|line| Movement::from_str(line.unwrap().as_ref()).unwrap());
+    Simulator::new(dial).count(movements, policy)
+}
+
+/// Same as [`count_zero_times`] over any [`LineSource`], a malformed movement ends the
+/// count with an [`io::ErrorKind::InvalidData`] error.
+pub fn count_zero_times_streaming(
+    source: impl LineSource,
+    policy: &impl CountingPolicy,
+) -> io::Result<usize> {
+    Simulator::new(Count::<MODULE>::from(START)).count_streaming(source, policy)
+}
+
+pub fn parse_sol(policy: &impl CountingPolicy) -> usize {
+    count_zero_times_streaming(input::open("./src/ed2025/day1/input.txt").unwrap(), policy).unwrap()
+}
@@ -1,39 +1,42 @@
-use std::{
-    fs::File,
-    io::{self, BufRead, BufReader},
-    str::FromStr,
-};
+use std::io;
 
-use crate::ed2025::day1::{Count, MODULE, Movement};
+use crate::{
+    ed2025::day1::{Dial, simulator::LandedOnZero, solution},
+    input::LineSource,
+};
 
 pub fn count_zero_times<S: AsRef<str>, const MODULE: usize>(
     lines: impl Iterator<Item = io::Result<S>>,
 ) -> usize {
-    let mut times: usize = 0;
-    let mut count = Count::<100>(50);
-    for line in lines {
-        match Movement::from_str(line.unwrap().as_ref()).unwrap() {
-            Movement::Left(n) => count.move_left(n),
-            Movement::Right(n) => count.move_right(n),
-        };
-
-        if count.get_inner() == 0 {
-            times += 1
-        }
-    }
-    times
+    solution::count_zero_times::<S, MODULE>(lines, &LandedOnZero)
+}
+
+/// Same as [`count_zero_times`] on any dial, whatever its size and starting position
+pub fn count_zero_times_with<S: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    dial: &mut impl Dial,
+) -> usize {
+    solution::count_zero_times_with(lines, dial, &LandedOnZero)
+}
+
+/// Same as [`count_zero_times`] over any [`LineSource`], a malformed movement ends the
+/// count with an [`io::ErrorKind::InvalidData`] error.
+pub fn count_zero_times_streaming(source: impl LineSource) -> io::Result<usize> {
+    solution::count_zero_times_streaming(source, &LandedOnZero)
 }
 
 pub fn parse_sol() -> usize {
-    let file = File::open("./src/ed2025/day1/input.txt").unwrap();
-    let lines = BufReader::new(file).lines();
-    count_zero_times::<_, { MODULE }>(lines)
+    solution::parse_sol(&LandedOnZero)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::{
+        ed2025::day1::{DynCount, MODULE, START},
+        input,
+    };
 
     #[test]
     fn test_count_zero_times() {
@@ -49,6 +52,18 @@ R14
 L82
 ";
         assert_eq!(count_zero_times::<_, MODULE>(lines.lines().map(Ok)), 3);
+        input::test::check_sources(
+            count_zero_times_streaming,
+            (lines, 3),
+            ("./src/ed2025/day1/input.txt", 1154),
+        );
+        assert!(count_zero_times_streaming("R1\nX2").is_err());
+        // Staying on zero counts again
+        assert_eq!(count_zero_times::<_, MODULE>("L50\nL0".lines().map(Ok)), 2);
+        assert_eq!(
+            count_zero_times_with(lines.lines().map(Ok), &mut DynCount::new(MODULE, START)),
+            3
+        );
     }
 
     #[test]
@@ -0,0 +1,194 @@
+use std::fmt::{self, Display, Write};
+
+use crate::{
+    ed2025::day1::{
+        Dial, Movement,
+        simulator::{Simulator, Step},
+    },
+    parse::{self, ParseResult},
+};
+
+pub const CSV_HEADER: &str = "direction,distance,before,after,zero_crossings";
+
+/// A movement and where it left the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub movement: Movement,
+    pub before: usize,
+    pub after: usize,
+    /// Times the dial pointed at zero, including where it stopped
+    pub zero_crossings: usize,
+}
+
+impl From<Step> for TraceEntry {
+    fn from(step: Step) -> Self {
+        Self {
+            movement: step.movement,
+            before: step.before,
+            after: step.after,
+            zero_crossings: step.zero_clicks(),
+        }
+    }
+}
+
+impl TraceEntry {
+    fn direction(&self) -> (char, usize) {
+        match self.movement {
+            Movement::Left(n) => ('L', n),
+            Movement::Right(n) => ('R', n),
+        }
+    }
+}
+
+/// First entry of a trace the simulator disagrees with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// 0-based position of the entry in the trace
+    pub index: usize,
+    pub expected: TraceEntry,
+    pub found: TraceEntry,
+}
+
+impl Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Step {} differs, trace has {:?} but the simulator did {:?}",
+            self.index, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ReplayMismatch {}
+
+/// Every step of a simulation, in order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace(pub Vec<TraceEntry>);
+
+impl Trace {
+    /// Applies every movement to `dial`, recording each step
+    pub fn record(dial: impl Dial, movements: impl IntoIterator<Item = Movement>) -> Self {
+        Self(
+            Simulator::new(dial)
+                .run(movements)
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.0
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for entry in &self.0 {
+            let (direction, distance) = entry.direction();
+            writeln!(
+                csv,
+                "{direction},{distance},{},{},{}",
+                entry.before, entry.after, entry.zero_crossings
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .0
+            .iter()
+            .map(|entry| {
+                let (direction, distance) = entry.direction();
+                format!(
+                    r#"{{"direction":"{direction}","distance":{distance},"before":{},"after":{},"zero_crossings":{}}}"#,
+                    entry.before, entry.after, entry.zero_crossings
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+
+    /// Reads what [`Self::to_csv`] writes, the header line is optional
+    pub fn from_csv(csv: &str) -> ParseResult<Self> {
+        let mut entries = vec![];
+        for (i, line) in csv.lines().enumerate() {
+            if line.trim().is_empty() || (i == 0 && line.trim() == CSV_HEADER) {
+                continue;
+            }
+            entries.push(parse::line(line, i + 1, |c| {
+                let movement = match c.next_char() {
+                    Some('L') => Movement::Left,
+                    Some('R') => Movement::Right,
+                    _ => return Err(c.error("Expected direction L or R")),
+                };
+                let mut field = || {
+                    c.expect(',')?;
+                    c.uint()
+                };
+                Ok(TraceEntry {
+                    movement: movement(field()?),
+                    before: field()?,
+                    after: field()?,
+                    zero_crossings: field()?,
+                })
+            })?);
+        }
+        Ok(Self(entries))
+    }
+
+    /// Runs the trace movements on `dial`, which should start where the trace does,
+    /// and checks every step matches.
+    pub fn replay(&self, dial: impl Dial) -> Result<(), ReplayMismatch> {
+        let mut sim = Simulator::new(dial);
+        for (index, expected) in self.0.iter().enumerate() {
+            let found = sim.step(expected.movement).into();
+            if *expected != found {
+                return Err(ReplayMismatch {
+                    index,
+                    expected: *expected,
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day1::{Count, START};
+
+    #[test]
+    fn test_csv_round_trip_and_replay() {
+        let movements = [Movement::Left(68), Movement::Right(48), Movement::Left(100)];
+        let trace = Trace::record(Count::<100>::from(START), movements);
+        let csv = trace.to_csv();
+        assert_eq!(
+            csv,
+            "direction,distance,before,after,zero_crossings
+L,68,50,82,1
+R,48,82,30,1
+L,100,30,30,1
+"
+        );
+        assert_eq!(Trace::from_csv(&csv), Ok(trace.clone()));
+        assert_eq!(trace.replay(Count::<100>::from(START)), Ok(()));
+
+        let mismatch = trace.replay(Count::<100>::from(0usize)).unwrap_err();
+        assert_eq!(mismatch.index, 0);
+        assert_eq!(mismatch.found.after, 32);
+
+        assert!(Trace::from_csv("U,1,2,3,4").is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let trace = Trace::record(Count::<100>::from(START), [Movement::Right(250)]);
+        assert_eq!(
+            trace.to_json(),
+            r#"[{"direction":"R","distance":250,"before":50,"after":0,"zero_crossings":3}]"#
+        );
+    }
+}
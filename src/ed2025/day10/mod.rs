@@ -3,9 +3,56 @@ const EXAMPLE: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
 
+use std::io;
+
+use crate::{
+    input::{LineSource, invalid_data},
+    parse::{self, ParseResult},
+};
+
+/// A line of the manual: `[lights] (button) (button) ... {joltages}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub lights: Vec<bool>,
+    /// Indexes of the lights toggled by every button
+    pub buttons: Vec<Vec<usize>>,
+    pub joltages: Vec<u16>,
+}
+
+pub fn parse_machine(line: &str, line_number: usize) -> ParseResult<Machine> {
+    parse::line(line, line_number, |c| {
+        let lights = c
+            .delimited('[', ']', |c| Ok(c.take_while(|l| l == '.' || l == '#')))?
+            .chars()
+            .map(|l| l == '#')
+            .collect();
+
+        let mut buttons = vec![];
+        c.skip_spaces();
+        while c.peek() == Some('(') {
+            buttons.push(c.delimited('(', ')', |c| c.sep_by(',', |c| c.uint()))?);
+            c.skip_spaces();
+        }
+
+        let joltages = c.delimited('{', '}', |c| c.sep_by(',', |c| c.uint()))?;
+
+        Ok(Machine {
+            lights,
+            buttons,
+            joltages,
+        })
+    })
+}
+
 pub mod part1 {
+    use super::*;
 
     pub fn solve(input: &str) -> usize {
+        solve_streaming(input).unwrap()
+    }
+
+    /// Machines are parsed and solved one line at a time
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
         let mut buttons = Vec::with_capacity(2 >> 4);
         let mut to_visit = Vec::with_capacity(2 >> 8);
         let mut to_visit_temp = Vec::with_capacity(2 >> 8);
@@ -28,73 +75,61 @@ pub mod part1 {
             }
         }
 
-        input
-            .lines()
-            .map(|l| {
-                let mut space_split = l.split(" ");
-                let mut desired = space_split.next().unwrap().chars();
-                assert_eq!(desired.next().unwrap(), '[');
-                assert_eq!(desired.next_back().unwrap(), ']');
-                let desired = desired
-                    .map(|s| match s {
-                        '.' => false,
-                        '#' => true,
-                        _ => panic!("unexpected light state"),
-                    })
-                    .enumerate()
-                    .filter(|(_, b)| *b)
-                    .map(|(i, _)| i);
-
-                let mut desired_lights = Lights::default();
-                for l in desired {
-                    desired_lights.set(l);
-                }
+        let mut solve_machine = |machine: Machine| {
+            let desired = machine
+                .lights
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b)
+                .map(|(i, _)| i);
+
+            let mut desired_lights = Lights::default();
+            for l in desired {
+                desired_lights.set(l);
+            }
 
-                let btns_i = space_split.filter_map(|parse| {
-                    let mut i = parse.chars();
-                    match (i.next(), i.next_back()) {
-                        (Some('('), Some(')')) => Some(
-                            i.as_str()
-                                .split(",")
-                                .map(|usz| usz.parse::<usize>().unwrap())
-                                .fold(Lights::default(), |mut acc, e| {
-                                    acc.set(e);
-                                    acc
-                                }),
-                        ),
-                        _ => None,
-                    }
-                });
+            let btns_i = machine.buttons.iter().map(|button| {
+                button.iter().fold(Lights::default(), |mut acc, e| {
+                    acc.set(*e);
+                    acc
+                })
+            });
 
-                buttons.clear();
-                for button in btns_i {
-                    buttons.push(button)
-                }
+            buttons.clear();
+            for button in btns_i {
+                buttons.push(button)
+            }
 
-                let mut button_presses = 1usize;
-                let first_layer = buttons.iter().map(|b| Lights::new(desired_lights, *b));
+            let mut button_presses = 1usize;
+            let first_layer = buttons.iter().map(|b| Lights::new(desired_lights, *b));
 
-                to_visit.clear();
-                to_visit.extend(first_layer);
+            to_visit.clear();
+            to_visit.extend(first_layer);
 
-                loop {
-                    if to_visit.iter().any(|n| n.is_empty()) {
-                        return button_presses;
-                    }
+            loop {
+                if to_visit.iter().any(|n| n.is_empty()) {
+                    return button_presses;
+                }
 
-                    button_presses += 1;
-                    let temp = to_visit
-                        .iter()
-                        .flat_map(|n| buttons.iter().map(|b| Lights::new(*n, *b)));
+                button_presses += 1;
+                let temp = to_visit
+                    .iter()
+                    .flat_map(|n| buttons.iter().map(|b| Lights::new(*n, *b)));
 
-                    to_visit_temp.clear();
-                    to_visit_temp.extend(temp);
+                to_visit_temp.clear();
+                to_visit_temp.extend(temp);
 
-                    to_visit.clear();
-                    to_visit.extend(to_visit_temp.iter());
-                }
-            })
-            .sum()
+                to_visit.clear();
+                to_visit.extend(to_visit_temp.iter());
+            }
+        };
+
+        let mut total = 0;
+        source.try_for_each_line(|line_number, line| {
+            total += solve_machine(parse_machine(line, line_number).map_err(invalid_data)?);
+            Ok(())
+        })?;
+        Ok(total)
     }
 
     #[cfg(test)]
@@ -109,6 +144,15 @@ pub mod part1 {
                 404
             )
         }
+
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 7),
+                ("./src/ed2025/day10/input.txt", 404),
+            );
+        }
     }
 }
 
@@ -118,6 +162,8 @@ pub mod part2 {
 
     use good_lp::{Expression, Solution, SolverModel, variable, variables};
 
+    use super::*;
+
     #[derive(Default, Debug, Clone, Copy)]
     struct Button(usize);
 
@@ -133,90 +179,78 @@ pub mod part2 {
     }
 
     pub fn solve(input: &str) -> usize {
+        solve_streaming(input).unwrap()
+    }
+
+    /// Machines are parsed and solved one line at a time
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
         let mut buttons = Vec::with_capacity(2 << 8);
         let mut button_vars = Vec::with_capacity(2 << 8);
         let mut joltages = Vec::with_capacity(2 << 8);
 
-        input
-            .lines()
-            .map(|l| {
-                let space_split = l.split(" ");
-                let mut desired = space_split.clone().last().unwrap().chars();
-                assert_eq!(desired.next().unwrap(), '{');
-                assert_eq!(desired.next_back().unwrap(), '}');
-
-                let desired = desired
-                    .as_str()
-                    .split(",")
-                    .map(|d| d.parse::<u16>().unwrap());
-
-                joltages.clear();
-                joltages.extend(desired);
-
-                let btns_i = space_split
-                    // Skip the illustration of lights on or off
-                    .skip(1)
-                    .filter_map(|parse| {
-                        let mut i = parse.chars();
-                        match (i.next(), i.next_back()) {
-                            (Some('('), Some(')')) => i
-                                .as_str()
-                                .split(",")
-                                .map(|usz| usz.parse::<usize>().unwrap())
-                                .try_fold(Button::default(), |mut acc, e| {
-                                    if joltages[e] == 0 {
-                                        None
-                                    } else {
-                                        acc.set(e);
-                                        Some(acc)
-                                    }
-                                }),
-                            _ => None,
-                        }
-                    });
-
-                buttons.clear();
-                for button in btns_i {
-                    buttons.push(button)
-                }
+        let mut solve_machine = |machine: Machine| {
+            joltages.clear();
+            joltages.extend(machine.joltages.iter().copied());
+
+            let btns_i = machine.buttons.iter().filter_map(|button| {
+                button.iter().try_fold(Button::default(), |mut acc, e| {
+                    if joltages[*e] == 0 {
+                        None
+                    } else {
+                        acc.set(*e);
+                        Some(acc)
+                    }
+                })
+            });
 
-                // for every button B [{usize}, {usize}, ...] of len joltages.len()
-                //   b_i element of B maps to light_i of joltages whose joltage will grow by b_i when B
-                //   is pressed
-                // We have an array of buttons (B) -> BTNS [B0, B1, ...]
-                // We want a linear combination of BTNS that sums up exactly to joltages
-                // such as: x * B0 + y * B1 + ... == joltages
-                // Where x + y + ... = S / S is the minimum possible
+            buttons.clear();
+            for button in btns_i {
+                buttons.push(button)
+            }
 
-                let mut vars = variables! {};
+            // for every button B [{usize}, {usize}, ...] of len joltages.len()
+            //   b_i element of B maps to light_i of joltages whose joltage will grow by b_i when B
+            //   is pressed
+            // We have an array of buttons (B) -> BTNS [B0, B1, ...]
+            // We want a linear combination of BTNS that sums up exactly to joltages
+            // such as: x * B0 + y * B1 + ... == joltages
+            // Where x + y + ... = S / S is the minimum possible
 
-                button_vars.clear();
-                for _ in &buttons {
-                    button_vars.push(vars.add(variable().integer().min(0)));
-                }
+            let mut vars = variables! {};
 
-                let mut problem = vars
-                    .minimise(Expression::sum(button_vars.iter()))
-                    .using(good_lp::microlp);
+            button_vars.clear();
+            for _ in &buttons {
+                button_vars.push(vars.add(variable().integer().min(0)));
+            }
 
-                for (light_idx, &target_val) in joltages.iter().enumerate() {
-                    let mut row_expression = Expression::from(0);
+            let mut problem = vars
+                .minimise(Expression::sum(button_vars.iter()))
+                .using(good_lp::microlp);
 
-                    for (btn_idx, button) in buttons.iter().enumerate() {
-                        let contribution = if button.is_set(light_idx) { 1 } else { 0 };
-                        if contribution > 0 {
-                            // Add (coefficient * variable) to the expression
-                            row_expression += button_vars[btn_idx] * contribution;
-                        }
+            for (light_idx, &target_val) in joltages.iter().enumerate() {
+                let mut row_expression = Expression::from(0);
+
+                for (btn_idx, button) in buttons.iter().enumerate() {
+                    let contribution = if button.is_set(light_idx) { 1 } else { 0 };
+                    if contribution > 0 {
+                        // Add (coefficient * variable) to the expression
+                        row_expression += button_vars[btn_idx] * contribution;
                     }
-                    problem = problem.with(row_expression.eq(target_val as i32));
                 }
+                problem = problem.with(row_expression.eq(target_val as i32));
+            }
 
-                let solution = problem.solve().unwrap();
+            let solution = problem.solve().unwrap();
 
-                solution.eval(Expression::sum(button_vars.iter())).round() as usize
-            })
-            .sum()
+            solution.eval(Expression::sum(button_vars.iter())).round() as usize
+        };
+
+        let mut total = 0;
+        source.try_for_each_line(|line_number, line| {
+            total += solve_machine(parse_machine(line, line_number).map_err(invalid_data)?);
+            Ok(())
+        })?;
+        Ok(total)
     }
 
     #[cfg(test)]
@@ -232,5 +266,14 @@ pub mod part2 {
                 16474
             )
         }
+
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 33),
+                ("./src/ed2025/day10/input.txt", 16474),
+            );
+        }
     }
 }
@@ -1,33 +1,51 @@
-pub mod part1 {
-    use std::collections::HashMap;
+use std::io;
 
-    pub fn solve(input: &str) -> usize {
-        let mut map = HashMap::new();
+use crate::{
+    graph::{self, Graph},
+    input::{LineSource, invalid_data},
+};
 
-        for line in input.lines() {
-            let mut parts = line.split(":");
-            map.entry(parts.next().unwrap())
-                .or_insert(Vec::with_capacity(5))
-                .extend(parts.next().unwrap().split_whitespace());
-        }
+pub fn parse_input(input: &str) -> Graph {
+    Graph::parse_adjacency(input).unwrap()
+}
 
-        let mut to_visit = vec!["you"];
-        let mut paths = 0;
-
-        while let Some(visiting) = to_visit.pop() {
-            if let Some(new_nodes) = map.get(visiting) {
-                for node in new_nodes {
-                    if *node == "out" {
-                        paths += 1;
-                    } else {
-                        // We got no infinite loops here! Nice
-                        to_visit.push(node);
-                    }
-                }
-            }
+/// Builds the graph from `source` without holding its text in memory
+pub fn parse_streaming(source: impl LineSource) -> io::Result<Graph> {
+    let mut graph = Graph::new();
+    source.try_for_each_line(|line_number, line| {
+        if !line.trim().is_empty() {
+            graph
+                .add_adjacency_line(line, line_number)
+                .map_err(invalid_data)?;
         }
+        Ok(())
+    })?;
+    Ok(graph)
+}
+
+/// Paths between two devices, 0 if any of them is not in the graph. `None` if the
+/// paths can loop forever.
+fn count_paths(graph: &Graph, from: &str, to: &str) -> Option<usize> {
+    match (graph.node(from), graph.node(to)) {
+        (Some(from), Some(to)) => graph::count_paths(graph, from, to),
+        _ => Some(0),
+    }
+}
+
+fn looping(from: &str, to: &str) -> io::Error {
+    invalid_data(format!("Paths from {from} to {to} go around a loop"))
+}
+
+pub mod part1 {
+    use super::*;
+
+    /// `None` if the paths can loop forever
+    pub fn solve(input: &str) -> Option<usize> {
+        count_paths(&parse_input(input), "you", "out")
+    }
 
-        paths
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
+        count_paths(&parse_streaming(source)?, "you", "out").ok_or_else(|| looping("you", "out"))
     }
 
     #[cfg(test)]
@@ -47,31 +65,46 @@ iii: out";
 
         #[test]
         fn test_solve() {
-            assert_eq!(solve(EXAMPLE), 5);
+            assert_eq!(solve(EXAMPLE), Some(5));
             assert_eq!(
                 solve(&std::fs::read_to_string("./src/ed2025/day11/input.txt").unwrap()),
-                699
+                Some(699)
+            );
+        }
+
+        #[test]
+        fn test_loops() {
+            // Loops away from the paths don't matter
+            assert_eq!(solve(&format!("{EXAMPLE}\naaa: zzz\nzzz: aaa")), Some(5));
+            let looping = format!("{EXAMPLE}\nggg: ccc");
+            assert_eq!(solve(&looping), None);
+            assert!(solve_streaming(looping.as_str()).is_err());
+        }
+
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 5),
+                ("./src/ed2025/day11/input.txt", 699),
             );
         }
     }
 }
 
 pub mod part2 {
-    use std::collections::HashMap;
+    use super::*;
 
-    // THANKS GEMINI FOR THIS IDEA!!!!!!!!
-    type Memo<'a> = HashMap<(&'a str, &'a str), usize>;
-
-    pub fn solve(input: &str) -> usize {
-        let mut map = HashMap::new();
+    /// `None` if the paths can loop forever
+    pub fn solve(input: &str) -> Option<usize> {
+        solve_graph(&parse_input(input))
+    }
 
-        for line in input.lines() {
-            let mut parts = line.split(":");
-            map.entry(parts.next().unwrap())
-                .or_insert(Vec::with_capacity(5))
-                .extend(parts.next().unwrap().split_whitespace());
-        }
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
+        solve_graph(&parse_streaming(source)?).ok_or_else(|| looping("svr", "out"))
+    }
 
+    fn solve_graph(graph: &Graph) -> Option<usize> {
         // THIS IDEA WAS MINE THO!! 😉😌
         // Everything needs to go through fft and dac, so we can precalculate the number of paths bewteen fft
         // and dac (fft-dac) and vice versa (dac-fft), also (fft-out) and (dac-out) number of paths
@@ -87,45 +120,14 @@ pub mod part2 {
         //          plus
         //      - dfs svr-fft * fft-dac-out
 
-        let mut memo = HashMap::new();
-
-        let svr_dac = count_paths("svr", &map, 0, "dac", &mut memo);
-        let svr_fft = count_paths("svr", &map, 0, "fft", &mut memo);
-        let fft_dac = count_paths("fft", &map, 0, "dac", &mut memo);
-        let dac_fft = count_paths("dac", &map, 0, "fft", &mut memo);
-        let dac_out = count_paths("dac", &map, 0, "out", &mut memo);
-        let fft_out = count_paths("fft", &map, 0, "out", &mut memo);
+        let svr_dac = count_paths(graph, "svr", "dac")?;
+        let svr_fft = count_paths(graph, "svr", "fft")?;
+        let fft_dac = count_paths(graph, "fft", "dac")?;
+        let dac_fft = count_paths(graph, "dac", "fft")?;
+        let dac_out = count_paths(graph, "dac", "out")?;
+        let fft_out = count_paths(graph, "fft", "out")?;
 
-        svr_dac * dac_fft * fft_out + svr_fft * fft_dac * dac_out
-    }
-
-    fn count_paths<'a>(
-        node: &'a str,
-        map: &HashMap<&'a str, Vec<&'a str>>,
-        depth: usize,
-        dest: &'a str,
-        memo: &mut Memo<'a>,
-    ) -> usize {
-        if let Some(&count) = memo.get(&(node, dest)) {
-            return count;
-        }
-
-        if node == dest {
-            println!("found out at: {depth}");
-            return 1;
-        }
-
-        let mut paths_found = 0;
-
-        if let Some(neighbors) = map.get(node) {
-            for &next in neighbors {
-                paths_found += count_paths(next, map, depth + 1, dest, memo);
-            }
-        }
-
-        memo.insert((node, dest), paths_found);
-
-        paths_found
+        Some(svr_dac * dac_fft * fft_out + svr_fft * fft_dac * dac_out)
     }
 
     #[cfg(test)]
@@ -148,11 +150,20 @@ hhh: out";
 
         #[test]
         fn test_solve() {
-            assert_eq!(solve(EXAMPLE), 2);
+            assert_eq!(solve(EXAMPLE), Some(2));
             println!("EXAMPLE passed");
             assert_eq!(
                 solve(&std::fs::read_to_string("./src/ed2025/day11/input.txt").unwrap()),
-                388893655378800
+                Some(388893655378800)
+            );
+        }
+
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 2),
+                ("./src/ed2025/day11/input.txt", 388893655378800),
             );
         }
     }
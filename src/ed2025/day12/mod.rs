@@ -38,10 +38,15 @@ const SHAPE_DIM: usize = 3;
 
 pub mod part1 {
 
+    use crate::{
+        grid::Grid,
+        parse::{self, Cursor, ParseResult},
+    };
+
     use super::*;
 
-    #[derive(Debug, Clone, Copy)]
-    pub struct Shape([[bool; SHAPE_DIM]; SHAPE_DIM]);
+    #[derive(Debug, Clone)]
+    pub struct Shape(Grid<bool>);
 
     #[derive(Clone, Copy, Debug)]
     pub struct BitShape {
@@ -53,16 +58,11 @@ pub mod part1 {
         fn from(value: Shape) -> Self {
             let mut ret = [0; SHAPE_DIM];
             let mut area = 0;
-            #[allow(clippy::needless_range_loop)]
-            for x in 0..SHAPE_DIM {
-                let mut rx = 0;
-                for y in 0..SHAPE_DIM {
-                    if value.0[x][y] {
-                        area += 1;
-                        rx |= 1 << y;
-                    }
+            for ((x, y), filled) in value.0.iter() {
+                if *filled {
+                    area += 1;
+                    ret[x] |= 1 << y;
                 }
-                ret[x] = rx
             }
 
             Self { mem: ret, area }
@@ -70,21 +70,31 @@ pub mod part1 {
     }
 
     impl Shape {
-        pub fn rotate_90(&self) -> Self {
-            let mut new_mem = [[false; SHAPE_DIM]; SHAPE_DIM];
+        fn parse_cell(c: char) -> Result<bool, String> {
+            match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(format!("Invalid shape char: {c}")),
+            }
+        }
 
-            for r in 0..SHAPE_DIM {
-                #[allow(clippy::needless_range_loop)]
-                for c in 0..SHAPE_DIM {
-                    new_mem[c][(SHAPE_DIM - 1) - r] = self.0[r][c]
-                }
+        pub fn from_grid(grid: Grid<bool>) -> Result<Self, String> {
+            if grid.rows() != SHAPE_DIM || grid.cols() != SHAPE_DIM {
+                return Err(format!(
+                    "Shape must be {SHAPE_DIM}x{SHAPE_DIM}, found {}x{}",
+                    grid.rows(),
+                    grid.cols()
+                ));
             }
+            Ok(Self(grid))
+        }
 
-            Shape(new_mem)
+        pub fn rotate_90(&self) -> Self {
+            Shape(self.0.rotate_cw())
         }
 
         pub fn get_rotations(&self) -> [Shape; 4] {
-            let r0 = Self(self.0);
+            let r0 = self.clone();
             let r1 = r0.rotate_90();
             let r2 = r1.rotate_90();
             let r3 = r2.rotate_90();
@@ -103,54 +113,51 @@ pub mod part1 {
         let mut shapes: Vec<Shape> = Vec::with_capacity(N_SHAPES);
         let mut regions: Vec<Region> = Vec::new();
 
-        let mut actual_shape = None;
-
-        for line in input.lines() {
-            // Shape index
-            if !line.contains("x") && line.contains(":") {
-                assert_eq!(actual_shape, None);
-                actual_shape = Some(([[false; SHAPE_DIM]; SHAPE_DIM], 0usize));
-                continue;
-            }
-
-            // We are parsing a shape
-            if let Some((shape, i)) = &mut actual_shape {
-                if *i == SHAPE_DIM {
-                    // End of shape
-                    assert!(line.is_empty());
-                    shapes.push(Shape(*shape));
-                    actual_shape = None;
-                    continue;
-                }
+        for (line, section) in parse::sections(input) {
+            let mut c = Cursor::at_line(section, line);
 
-                for (j, char) in line.chars().enumerate() {
-                    shape[*i][j] = char == '#'
+            if section.lines().next().unwrap().contains("x") {
+                // Parse regions part
+                while !c.is_eof() {
+                    regions.push(parse_region(&mut c).unwrap());
+                    c.end_of_line().unwrap();
                 }
-                *i += 1;
-
                 continue;
             }
 
-            // Parse regions part
-            assert!(line.contains("x"));
-            let mut parts = line.split(":");
-            let mut dimensions = parts.next().unwrap().split("x");
-            let dimensions: (usize, usize) = (
-                dimensions.next().unwrap().parse().unwrap(),
-                dimensions.next().unwrap().parse().unwrap(),
-            );
+            shapes.push(parse_shape(&mut c, shapes.len()).unwrap());
+            c.finish().unwrap();
+        }
 
-            let mut to_assign_desired = parts
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .map(|p| p.parse::<usize>().unwrap());
-            let desired_parts = std::array::from_fn(|_| to_assign_desired.next().unwrap());
+        (shapes.try_into().unwrap(), regions)
+    }
 
-            regions.push((dimensions, desired_parts));
+    /// `index:` followed by the shape rows
+    fn parse_shape(c: &mut Cursor, index: usize) -> ParseResult<Shape> {
+        if c.uint::<usize>()? != index {
+            return Err(c.error(format!("Expected shape {index}")));
         }
+        c.expect(':')?;
+        c.end_of_line()?;
+        let grid = c.grid(Shape::parse_cell)?;
+        Shape::from_grid(grid).map_err(|e| c.error(e))
+    }
 
-        (shapes.try_into().unwrap(), regions)
+    /// `WxL: n0 n1 ...` with the number of every shape
+    fn parse_region(c: &mut Cursor) -> ParseResult<Region> {
+        let width = c.uint()?;
+        c.expect('x')?;
+        let length = c.uint()?;
+        c.expect(':')?;
+
+        let mut desired_parts = [0; N_SHAPES];
+        for desired in desired_parts.iter_mut() {
+            c.skip_spaces();
+            *desired = c.uint()?;
+        }
+        c.skip_spaces();
+
+        Ok(((width, length), desired_parts))
     }
 
     #[derive(Debug, Clone, Copy)]
@@ -175,7 +182,7 @@ pub mod part1 {
     }
 
     impl WorkingRegion {
-        pub fn new(((width, lenght), desired_shapes): Region, shapes: &[Shape; N_SHAPES]) -> Self {
+        pub fn new(((width, length), desired_shapes): Region, shapes: &[Shape; N_SHAPES]) -> Self {
             let mut desired_shapes = desired_shapes
                 .iter()
                 .enumerate()
@@ -184,9 +191,9 @@ pub mod part1 {
             let desired_shapes = std::array::from_fn(|_| desired_shapes.next().unwrap());
 
             Self {
-                mem: Vec::from_iter(std::iter::repeat_n(Row::zeros_len(width), lenght)),
+                mem: Vec::from_iter(std::iter::repeat_n(Row::zeros_len(width), length)),
                 desired_shapes,
-                free_tiles: width * lenght,
+                free_tiles: width * length,
             }
         }
 
@@ -290,10 +297,18 @@ pub mod part1 {
     }
 
     pub fn solve(input: &str) -> usize {
+        #[cfg(feature = "parallel")]
         use rayon::prelude::*;
+
         let (shapes, regions) = parse_input(input);
+
+        // Regions are independent from each other
+        #[cfg(feature = "parallel")]
+        let regions = regions.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let regions = regions.into_iter();
+
         regions
-            .into_par_iter()
             .map(|r| WorkingRegion::new(r, &shapes))
             .map(|wr| wr.can_fit_shapes())
             .filter(|wr| *wr)
@@ -0,0 +1,294 @@
+//! Counting and adding repeated-digit numbers without visiting every number of a range.
+//!
+//! A number of `len` digits made of a `d` digits pattern `p` repeated `len / d` times is
+//! `p * R` where `R = 1 0..0 1 0..0 1 ...` (`len / d` ones, `d - 1` zeros in between).
+//! Every pattern in a range of patterns gives a number in a range of numbers, so the
+//! numbers of each shape are an arithmetic series.
+
+/// How many numbers and their sum
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Totals {
+    pub count: u128,
+    pub sum: u128,
+}
+
+impl Totals {
+    pub(crate) fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self {
+            count: self.count.checked_add(other.count)?,
+            sum: self.sum.checked_add(other.sum)?,
+        })
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Self {
+            count: self.count.checked_sub(other.count)?,
+            sum: self.sum.checked_sub(other.sum)?,
+        })
+    }
+}
+
+/// Number of digits of `n` in `base`, 0 has one
+pub fn digits(n: u128, base: u32) -> u32 {
+    n.checked_ilog(base as u128).unwrap_or(0) + 1
+}
+
+/// `1 0..0 1 0..0 1 ...` in `base` with `times` ones, every one `width` digits apart
+fn repunit(base: u32, width: u32, times: u32) -> Option<u128> {
+    if times == 1 {
+        return Some(1);
+    }
+    let shift = (base as u128).checked_pow(width)?;
+    (0..times).try_fold(0u128, |acc, _| acc.checked_mul(shift)?.checked_add(1))
+}
+
+/// Smallest and biggest numbers with `len` digits in `base`, the smallest one starts
+/// with a zero if `leading_zeros`.
+fn length_bounds(base: u32, len: u32, leading_zeros: bool) -> (u128, u128) {
+    let base = base as u128;
+    let min = if leading_zeros { 0 } else { base.pow(len - 1) };
+    let max = base.checked_pow(len).map_or(u128::MAX, |n| n - 1);
+    (min, max)
+}
+
+/// How the numbers being counted are written
+#[derive(Clone, Copy)]
+struct Layout {
+    base: u32,
+    len: u32,
+    leading_zeros: bool,
+}
+
+impl Layout {
+    /// First and last `width` digits pattern whose repetition is in `lo..=hi`, and the
+    /// repunit turning a pattern into its number. `None` if no pattern is in range.
+    ///
+    /// A repunit over `u128::MAX` (padded to many digits) is past `hi`, only the pattern
+    /// of zeros can be in range then and it is 0 whatever the repunit, given as 0.
+    fn patterns(&self, lo: u128, hi: u128, width: u32) -> Option<(u128, u128, u128)> {
+        let (pattern_min, pattern_max) = length_bounds(self.base, width, self.leading_zeros);
+        match repunit(self.base, width, self.len / width) {
+            Some(r) => {
+                let first = pattern_min.max(lo.div_ceil(r));
+                let last = pattern_max.min(hi / r);
+                (first <= last).then_some((first, last, r))
+            }
+            None => (pattern_min == 0 && lo == 0).then_some((0, 0, 0)),
+        }
+    }
+
+    /// Numbers in `lo..=hi` that repeat a `width` digits pattern, whatever their
+    /// shortest pattern is.
+    fn with_period(&self, lo: u128, hi: u128, width: u32) -> Option<Totals> {
+        let Some((first, last, r)) = self.patterns(lo, hi, width) else {
+            return Some(Totals::default());
+        };
+
+        let count = last - first + 1;
+        // One of the two is even
+        let patterns_sum = if count % 2 == 0 {
+            (count / 2).checked_mul(first.checked_add(last)?)?
+        } else {
+            count.checked_mul(first.checked_add(last)? / 2)?
+        };
+        Some(Totals {
+            count,
+            sum: patterns_sum.checked_mul(r)?,
+        })
+    }
+
+    /// Numbers in `lo..=hi` whose pattern is repeated `k` times for some `k` that
+    /// `accepts`.
+    fn repeated(&self, lo: u128, hi: u128, accepts: &impl Fn(u32) -> bool) -> Option<Totals> {
+        let len = self.len;
+        let divisors: Vec<u32> = (1..=len).filter(|d| len.is_multiple_of(*d)).collect();
+        // A number whose shortest pattern is `width` digits long is that pattern repeated
+        // `len / width` times, but also any longer pattern made of it repeated fewer times.
+        let is_accepted = |width: u32| {
+            let repeats = len / width;
+            (1..=repeats).any(|k| repeats.is_multiple_of(k) && accepts(k))
+        };
+
+        // Numbers whose shortest pattern is exactly divisors[i] digits long, patterns made
+        // of shorter ones are taken out.
+        let mut exact: Vec<Totals> = Vec::with_capacity(divisors.len());
+        let mut totals = Totals::default();
+        for (i, width) in divisors.iter().enumerate() {
+            // Only widths accepted or dividing an accepted one are needed, skipping the
+            // others avoids overflowing on sums that are never used
+            if !divisors[i..]
+                .iter()
+                .any(|w| w.is_multiple_of(*width) && is_accepted(*w))
+            {
+                exact.push(Totals::default());
+                continue;
+            }
+
+            let shorter = divisors[..i]
+                .iter()
+                .zip(&exact)
+                .filter(|(d, _)| width.is_multiple_of(**d))
+                .try_fold(Totals::default(), |acc, (_, t)| acc.checked_add(*t))?;
+            let current = self.with_period(lo, hi, *width)?.checked_sub(shorter)?;
+            exact.push(current);
+
+            if is_accepted(*width) {
+                totals = totals.checked_add(current)?;
+            }
+        }
+        Some(totals)
+    }
+
+    /// Every number in `lo..=hi` counted by [`Self::repeated`], unsorted and with
+    /// duplicates.
+    fn repeated_ids(&self, lo: u128, hi: u128, accepts: &impl Fn(u32) -> bool) -> Vec<u128> {
+        let mut ids = vec![];
+        for width in (1..=self.len).filter(|w| self.len.is_multiple_of(*w)) {
+            if !accepts(self.len / width) {
+                continue;
+            }
+            if let Some((first, last, r)) = self.patterns(lo, hi, width) {
+                ids.extend((first..=last).map(|p| p * r));
+            }
+        }
+        ids
+    }
+}
+
+/// Numbers in `start..=end` made of a digit pattern repeated `k` times, for any `k`
+/// that `accepts`. `None` if the sum does not fit in a `u128`.
+pub fn repeated_in_range(start: u128, end: u128, accepts: impl Fn(u32) -> bool) -> Option<Totals> {
+    repeated_in_range_in(start, end, 10, 0, accepts)
+}
+
+/// Splits `start..=end` by the length of its numbers, in increasing order.
+fn layouts(start: u128, end: u128, base: u32, pad_to: u32) -> Vec<(Layout, u128, u128)> {
+    assert!(base >= 2, "Numbers need a base of at least 2");
+    let mut layouts = vec![];
+    let mut start = start;
+
+    if pad_to > 0 {
+        let (_, padded_max) = length_bounds(base, pad_to, true);
+        if start <= padded_max {
+            let layout = Layout {
+                base,
+                len: pad_to,
+                leading_zeros: true,
+            };
+            layouts.push((layout, start, end.min(padded_max)));
+            match padded_max.checked_add(1) {
+                Some(next) => start = next,
+                None => return layouts,
+            }
+        }
+    }
+
+    // 0 can't be written without a leading zero
+    let start = start.max(1);
+    if start > end {
+        return layouts;
+    }
+
+    for len in digits(start, base)..=digits(end, base) {
+        let (min, max) = length_bounds(base, len, false);
+        let layout = Layout {
+            base,
+            len,
+            leading_zeros: false,
+        };
+        layouts.push((layout, start.max(min), end.min(max)));
+    }
+    layouts
+}
+
+/// Same as [`repeated_in_range`] writing the numbers in `base`, numbers shorter than
+/// `pad_to` digits are padded with leading zeros. Without padding 0 is never counted.
+pub fn repeated_in_range_in(
+    start: u128,
+    end: u128,
+    base: u32,
+    pad_to: u32,
+    accepts: impl Fn(u32) -> bool,
+) -> Option<Totals> {
+    layouts(start, end, base, pad_to)
+        .into_iter()
+        .try_fold(Totals::default(), |acc, (layout, lo, hi)| {
+            acc.checked_add(layout.repeated(lo, hi, &accepts)?)
+        })
+}
+
+/// The numbers [`repeated_in_range_in`] counts, in increasing order. Meant for
+/// reports, every number is built so wide ranges take long.
+pub fn repeated_ids_in(
+    start: u128,
+    end: u128,
+    base: u32,
+    pad_to: u32,
+    accepts: impl Fn(u32) -> bool,
+) -> Vec<u128> {
+    let mut ids: Vec<u128> = layouts(start, end, base, pad_to)
+        .into_iter()
+        .flat_map(|(layout, lo, hi)| layout.repeated_ids(lo, hi, &accepts))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn brute_force(start: u128, end: u128, accepts: impl Fn(u32) -> bool) -> Totals {
+        let is_repeated = |n: u128| {
+            let s = n.to_string();
+            (1..=s.len() as u32).any(|k| {
+                s.len().is_multiple_of(k as usize)
+                    && accepts(k)
+                    && s == s[..s.len() / k as usize].repeat(k as usize)
+            })
+        };
+        (start..=end)
+            .filter(|n| is_repeated(*n))
+            .fold(Totals::default(), |acc, n| Totals {
+                count: acc.count + 1,
+                sum: acc.sum + n,
+            })
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for (start, end) in [(1, 200_000), (95, 115), (998, 1012), (222_220, 1_222_224)] {
+            assert_eq!(
+                repeated_in_range(start, end, |k| k == 2),
+                Some(brute_force(start, end, |k| k == 2))
+            );
+            assert_eq!(
+                repeated_in_range(start, end, |k| k >= 2),
+                Some(brute_force(start, end, |k| k >= 2))
+            );
+            assert_eq!(
+                repeated_in_range(start, end, |k| k == 3 || k == 4),
+                Some(brute_force(start, end, |k| k == 3 || k == 4))
+            );
+            let ids = repeated_ids_in(start, end, 10, 0, |k| k >= 2);
+            assert_eq!(ids.len() as u128, brute_force(start, end, |k| k >= 2).count);
+        }
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        // 11, 22, ..., 99
+        assert_eq!(
+            repeated_in_range(0, 99, |k| k == 2),
+            Some(Totals { count: 9, sum: 495 })
+        );
+        let totals = repeated_in_range(0, 10u128.pow(24), |k| k == 2).unwrap();
+        assert_eq!(totals.count, 10u128.pow(12) - 1);
+        assert_eq!(repeated_in_range(0, u128::MAX, |k| k >= 2), None);
+        assert_eq!(
+            repeated_in_range(u128::MAX - 10, u128::MAX, |k| k >= 2),
+            Some(Totals::default())
+        );
+    }
+}
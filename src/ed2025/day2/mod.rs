@@ -1,15 +1,77 @@
-pub mod part1 {
+use crate::parse::{Cursor, ParseResult};
 
-    trait IsInvalidId {
-        fn is_invalid_id(&self) -> bool;
-    }
+pub mod counting;
+pub mod policy;
+
+use counting::Totals;
+use policy::RepetitionPolicy;
+
+/// Comma separated `start-end` pairs, both included
+pub fn parse_ranges(input: &str) -> ParseResult<Vec<(usize, usize)>> {
+    let mut cursor = Cursor::new(input);
+    let ranges = cursor.sep_by(',', |c| {
+        let start = c.uint()?;
+        c.expect('-')?;
+        let end = c.uint()?;
+        if end < start {
+            return Err(c.error(format!("Range ends before its start {start}")));
+        }
+        Ok((start, end))
+    })?;
+    cursor.finish()?;
+    Ok(ranges)
+}
 
-    impl IsInvalidId for usize {
-        fn is_invalid_id(&self) -> bool {
-            let s = self.to_string();
-            s[..s.len() / 2] == s[s.len() / 2..]
+/// Sorts the ranges and joins the overlapping or adjacent ones
+pub fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end)
+            }
+            _ => merged.push((start, end)),
         }
     }
+    merged
+}
+
+/// Invalid IDs in all the ranges, an ID in several ranges is only counted once.
+/// `None` if their sum does not fit in a `u128`.
+pub fn invalid_totals(ranges: &[(usize, usize)], policy: &RepetitionPolicy) -> Option<Totals> {
+    merge_ranges(ranges.to_vec())
+        .into_iter()
+        .try_fold(Totals::default(), |acc, (start, end)| {
+            acc.checked_add(policy.totals(start as u128, end as u128)?)
+        })
+}
+
+/// Invalid IDs found in one of the input ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeReport {
+    pub start: usize,
+    pub end: usize,
+    pub invalid_ids: Vec<u128>,
+}
+
+/// What every range, in input order, contributes. IDs in overlapping ranges show up
+/// in all of them.
+pub fn report(ranges: &[(usize, usize)], policy: &RepetitionPolicy) -> Vec<RangeReport> {
+    ranges
+        .iter()
+        .map(|&(start, end)| RangeReport {
+            start,
+            end,
+            invalid_ids: policy.invalid_ids(start as u128, end as u128),
+        })
+        .collect()
+}
+
+pub mod part1 {
+    use super::*;
+
+    pub const POLICY: RepetitionPolicy = RepetitionPolicy::exactly(2);
 
     pub struct Range {
         pub start: usize,
@@ -18,26 +80,34 @@ pub mod part1 {
 
     impl Range {
         pub fn invalid_ids_added(&self) -> usize {
-            (self.start..=self.end)
-                .map(|num| if num.is_invalid_id() { num } else { 0 })
-                .sum()
+            POLICY
+                .sum(self.start as u128, self.end as u128)
+                .and_then(|sum| usize::try_from(sum).ok())
+                .expect("Sum of invalid IDs overflows")
         }
     }
 
     pub fn parse_input(input: &str) -> Vec<Range> {
-        input
-            .split(",")
-            .map(|range| range.split("-"))
-            .map(|mut r| [r.next().unwrap(), r.next().unwrap()])
-            .map(|[low, up]| Range {
-                start: low.parse().unwrap(),
-                end: up.parse().unwrap(),
-            })
+        parse_ranges(input)
+            .unwrap()
+            .into_iter()
+            .map(|(start, end)| Range { start, end })
             .collect()
     }
 
+    fn totals(p_input: Vec<Range>) -> Totals {
+        let ranges: Vec<(usize, usize)> = p_input.iter().map(|r| (r.start, r.end)).collect();
+        invalid_totals(&ranges, &POLICY).expect("Sum of invalid IDs overflows")
+    }
+
+    /// Sum of the invalid IDs, overlapping ranges are merged first
     pub fn calculate_occurances(p_input: Vec<Range>) -> usize {
-        p_input.iter().map(|r| r.invalid_ids_added()).sum()
+        usize::try_from(totals(p_input).sum).expect("Sum of invalid IDs overflows")
+    }
+
+    /// Number of invalid IDs, overlapping ranges are merged first
+    pub fn count_occurances(p_input: Vec<Range>) -> usize {
+        usize::try_from(totals(p_input).count).expect("Number of invalid IDs overflows")
     }
 
     #[cfg(test)]
@@ -45,6 +115,7 @@ pub mod part1 {
         use std::{fs::File, io::Read};
 
         use super::*;
+        use crate::ed2025::day2::policy::IsInvalidId;
 
         pub const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
@@ -56,12 +127,12 @@ pub mod part1 {
 
         #[test]
         fn test_is_invalid_id() {
-            assert!(!0usize.is_invalid_id());
-            assert!(!1usize.is_invalid_id());
-            assert!(!2usize.is_invalid_id());
-            assert!(!2usize.is_invalid_id());
-            assert!(22usize.is_invalid_id());
-            assert!(102102usize.is_invalid_id());
+            assert!(!0usize.is_invalid_id(&POLICY));
+            assert!(!1usize.is_invalid_id(&POLICY));
+            assert!(!2usize.is_invalid_id(&POLICY));
+            assert!(!2usize.is_invalid_id(&POLICY));
+            assert!(22usize.is_invalid_id(&POLICY));
+            assert!(102102usize.is_invalid_id(&POLICY));
         }
 
         #[test]
@@ -70,6 +141,27 @@ pub mod part1 {
             assert_eq!(sum, 1227775554)
         }
 
+        #[test]
+        fn test_overlapping_ranges() {
+            assert_eq!(count_occurances(parse_input(EXAMPLE)), 8);
+            // 11 and 22 once, plus 33
+            assert_eq!(calculate_occurances(parse_input("10-22,11-15,23-33")), 66);
+            assert_eq!(count_occurances(parse_input("10-22,11-15,23-33")), 3);
+
+            let ranges = parse_ranges("11-22,95-115,15-40").unwrap();
+            let report = report(&ranges, &POLICY);
+            assert_eq!(report[0].invalid_ids, vec![11, 22]);
+            assert_eq!(report[1].invalid_ids, vec![99]);
+            assert_eq!(report[2].invalid_ids, vec![22, 33]);
+
+            assert_eq!(
+                merge_ranges(vec![(20, 30), (1, 5), (6, 8), (25, 26), (10, 12)]),
+                vec![(1, 8), (10, 12), (20, 30)]
+            );
+            assert!(parse_ranges("11-22,30-25").is_err());
+            assert!(parse_ranges("11-22,30").is_err());
+        }
+
         #[test]
         fn test_solution1_input() {
             let mut input = String::new();
@@ -82,41 +174,9 @@ pub mod part1 {
 }
 
 pub mod part2 {
+    use super::*;
 
-    trait IsInvalidId {
-        fn is_invalid_id(&self) -> bool;
-    }
-
-    impl IsInvalidId for usize {
-        fn is_invalid_id(&self) -> bool {
-            self.to_string().is_invalid_id()
-        }
-    }
-
-    impl IsInvalidId for str {
-        // My approach was this (inefficient)... 😔
-        // fn is_invalid_id(&self) -> bool {
-        //     (2..=self.len())
-        //         .filter(|n_parts| self.len().is_multiple_of(*n_parts))
-        //         .any(|n_parts| {
-        //             let p_size = self.len() / n_parts;
-        //             let mut iter = (0..n_parts).map(|i| &self[i * p_size..(i + 1) * p_size]);
-        //             if let Some(true) = iter.next().map(|first| iter.all(|e| e == first)) {
-        //                 return true;
-        //             }
-        //             false
-        //         })
-        // }
-
-        // So cool
-        fn is_invalid_id(&self) -> bool {
-            if self.len() < 2 {
-                return false;
-            }
-            let doubled = self.repeat(2);
-            doubled[1..doubled.len() - 1].contains(self)
-        }
-    }
+    pub const POLICY: RepetitionPolicy = RepetitionPolicy::at_least(2);
 
     pub struct Range {
         pub start: usize,
@@ -125,26 +185,34 @@ pub mod part2 {
 
     impl Range {
         pub fn invalid_ids_added(&self) -> usize {
-            (self.start..=self.end)
-                .map(|num| if num.is_invalid_id() { num } else { 0 })
-                .sum()
+            POLICY
+                .sum(self.start as u128, self.end as u128)
+                .and_then(|sum| usize::try_from(sum).ok())
+                .expect("Sum of invalid IDs overflows")
         }
     }
 
     pub fn parse_input(input: &str) -> Vec<Range> {
-        input
-            .split(",")
-            .map(|range| range.split("-"))
-            .map(|mut r| [r.next().unwrap(), r.next().unwrap()])
-            .map(|[low, up]| Range {
-                start: low.parse().unwrap(),
-                end: up.parse().unwrap(),
-            })
+        parse_ranges(input)
+            .unwrap()
+            .into_iter()
+            .map(|(start, end)| Range { start, end })
             .collect()
     }
 
+    fn totals(p_input: Vec<Range>) -> Totals {
+        let ranges: Vec<(usize, usize)> = p_input.iter().map(|r| (r.start, r.end)).collect();
+        invalid_totals(&ranges, &POLICY).expect("Sum of invalid IDs overflows")
+    }
+
+    /// Sum of the invalid IDs, overlapping ranges are merged first
     pub fn calculate_occurances(p_input: Vec<Range>) -> usize {
-        p_input.iter().map(|r| r.invalid_ids_added()).sum()
+        usize::try_from(totals(p_input).sum).expect("Sum of invalid IDs overflows")
+    }
+
+    /// Number of invalid IDs, overlapping ranges are merged first
+    pub fn count_occurances(p_input: Vec<Range>) -> usize {
+        usize::try_from(totals(p_input).count).expect("Number of invalid IDs overflows")
     }
 
     #[cfg(test)]
@@ -152,6 +220,7 @@ pub mod part2 {
         use std::{fs::File, io::Read};
 
         use super::*;
+        use crate::ed2025::day2::policy::IsInvalidId;
 
         pub const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
@@ -163,14 +232,14 @@ pub mod part2 {
 
         #[test]
         fn test_is_invalid_id() {
-            assert!(!0usize.is_invalid_id());
-            assert!(!1usize.is_invalid_id());
-            assert!(!2usize.is_invalid_id());
-            assert!(!2usize.is_invalid_id());
-            assert!(22usize.is_invalid_id());
-            assert!(222usize.is_invalid_id());
-            assert!(102102usize.is_invalid_id());
-            assert!(102102102usize.is_invalid_id());
+            assert!(!0usize.is_invalid_id(&POLICY));
+            assert!(!1usize.is_invalid_id(&POLICY));
+            assert!(!2usize.is_invalid_id(&POLICY));
+            assert!(!2usize.is_invalid_id(&POLICY));
+            assert!(22usize.is_invalid_id(&POLICY));
+            assert!(222usize.is_invalid_id(&POLICY));
+            assert!(102102usize.is_invalid_id(&POLICY));
+            assert!(102102102usize.is_invalid_id(&POLICY));
         }
 
         #[test]
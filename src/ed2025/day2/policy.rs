@@ -0,0 +1,224 @@
+use crate::ed2025::day2::counting::{self, Totals};
+
+/// How IDs shorter than a width are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeadingZeros {
+    /// As usual, 0 is never an invalid ID
+    None,
+    /// Padded with zeros up to the given number of digits, so `0101` is `101` in 4 digits
+    PadTo(u32),
+}
+
+/// Which IDs made of a repeated digit pattern are invalid. Only built through the
+/// constructors below, so the base is always at least 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepetitionPolicy {
+    min_repeats: u32,
+    max_repeats: u32,
+    base: u32,
+    leading_zeros: LeadingZeros,
+}
+
+impl RepetitionPolicy {
+    /// Pattern repeated exactly `k` times, part 1 is `exactly(2)`
+    pub const fn exactly(k: u32) -> Self {
+        Self {
+            min_repeats: k,
+            max_repeats: k,
+            base: 10,
+            leading_zeros: LeadingZeros::None,
+        }
+    }
+
+    /// Pattern repeated `k` or more times, part 2 is `at_least(2)`
+    pub const fn at_least(k: u32) -> Self {
+        Self {
+            min_repeats: k,
+            max_repeats: u32::MAX,
+            base: 10,
+            leading_zeros: LeadingZeros::None,
+        }
+    }
+
+    pub const fn at_most(self, max_repeats: u32) -> Self {
+        Self {
+            max_repeats,
+            ..self
+        }
+    }
+
+    /// Panics if `base` is lower than 2
+    pub const fn in_base(self, base: u32) -> Self {
+        assert!(base >= 2, "Numbers need a base of at least 2");
+        Self { base, ..self }
+    }
+
+    pub const fn padded_to(self, width: u32) -> Self {
+        Self {
+            leading_zeros: LeadingZeros::PadTo(width),
+            ..self
+        }
+    }
+
+    pub const fn min_repeats(&self) -> u32 {
+        self.min_repeats
+    }
+
+    pub const fn max_repeats(&self) -> u32 {
+        self.max_repeats
+    }
+
+    pub const fn base(&self) -> u32 {
+        self.base
+    }
+
+    pub const fn leading_zeros(&self) -> LeadingZeros {
+        self.leading_zeros
+    }
+
+    /// A pattern repeated `k` times makes an invalid ID
+    pub fn accepts(&self, k: u32) -> bool {
+        (self.min_repeats..=self.max_repeats).contains(&k)
+    }
+
+    fn pad_to(&self) -> u32 {
+        match self.leading_zeros {
+            LeadingZeros::None => 0,
+            LeadingZeros::PadTo(width) => width,
+        }
+    }
+
+    pub fn is_invalid(&self, id: u128) -> bool {
+        let pad_to = self.pad_to() as usize;
+        if id == 0 && pad_to == 0 {
+            return false;
+        }
+
+        // Least significant first, the order does not change the periods
+        let mut digits = vec![];
+        let mut rest = id;
+        while rest > 0 || digits.is_empty() {
+            digits.push(rest % self.base as u128);
+            rest /= self.base as u128;
+        }
+        if digits.len() < pad_to {
+            digits.resize(pad_to, 0);
+        }
+
+        let len = digits.len();
+        (1..=len as u32)
+            .filter(|k| len.is_multiple_of(*k as usize) && self.accepts(*k))
+            .any(|k| {
+                let width = len / k as usize;
+                digits
+                    .iter()
+                    .enumerate()
+                    .all(|(i, d)| *d == digits[i % width])
+            })
+    }
+
+    /// Invalid IDs in `start..=end`, `None` if their sum does not fit in a `u128`
+    pub fn totals(&self, start: u128, end: u128) -> Option<Totals> {
+        counting::repeated_in_range_in(start, end, self.base, self.pad_to(), |k| self.accepts(k))
+    }
+
+    /// Every invalid ID in `start..=end`, in increasing order
+    pub fn invalid_ids(&self, start: u128, end: u128) -> Vec<u128> {
+        counting::repeated_ids_in(start, end, self.base, self.pad_to(), |k| self.accepts(k))
+    }
+
+    pub fn sum(&self, start: u128, end: u128) -> Option<u128> {
+        self.totals(start, end).map(|t| t.sum)
+    }
+
+    pub fn count(&self, start: u128, end: u128) -> Option<u128> {
+        self.totals(start, end).map(|t| t.count)
+    }
+}
+
+pub trait IsInvalidId {
+    fn is_invalid_id(&self, policy: &RepetitionPolicy) -> bool;
+}
+
+macro_rules! impl_is_invalid_id {
+    ($($t:ty),*) => {
+        $(
+            impl IsInvalidId for $t {
+                fn is_invalid_id(&self, policy: &RepetitionPolicy) -> bool {
+                    policy.is_invalid(*self as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_is_invalid_id!(u32, u64, u128, usize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_totals(policy: RepetitionPolicy, start: u128, end: u128) {
+        let (count, sum) = (start..=end)
+            .filter(|id| id.is_invalid_id(&policy))
+            .fold((0, 0), |(count, sum), id| (count + 1, sum + id));
+        assert_eq!(
+            policy.totals(start, end),
+            Some(Totals { count, sum }),
+            "{policy:?} in {start}..={end}"
+        );
+        let ids = policy.invalid_ids(start, end);
+        assert_eq!(ids.len() as u128, count, "{policy:?} in {start}..={end}");
+        assert!(ids.iter().all(|id| policy.is_invalid(*id)));
+    }
+
+    #[test]
+    fn test_is_invalid() {
+        let twice = RepetitionPolicy::exactly(2);
+        assert!(1212usize.is_invalid_id(&twice));
+        assert!(!121212usize.is_invalid_id(&twice));
+        assert!(!0usize.is_invalid_id(&twice));
+
+        let at_most_three = RepetitionPolicy::at_least(2).at_most(3);
+        assert!(121212u64.is_invalid_id(&at_most_three));
+        assert!(!11111111u64.is_invalid_id(&RepetitionPolicy::exactly(3)));
+        assert!(11111111u64.is_invalid_id(&RepetitionPolicy::at_least(8)));
+
+        // 0b1010
+        assert!(10u32.is_invalid_id(&RepetitionPolicy::exactly(2).in_base(2)));
+        // 0101
+        assert!(101u32.is_invalid_id(&RepetitionPolicy::exactly(2).padded_to(4)));
+        assert!(0u32.is_invalid_id(&RepetitionPolicy::exactly(2).padded_to(4)));
+
+        let policy = RepetitionPolicy::at_least(2).in_base(16).padded_to(4);
+        assert_eq!((policy.min_repeats(), policy.max_repeats()), (2, u32::MAX));
+        assert_eq!(policy.base(), 16);
+        assert_eq!(policy.leading_zeros(), LeadingZeros::PadTo(4));
+        assert!(std::panic::catch_unwind(|| RepetitionPolicy::exactly(2).in_base(1)).is_err());
+    }
+
+    #[test]
+    fn test_totals() {
+        for policy in [
+            RepetitionPolicy::exactly(2),
+            RepetitionPolicy::exactly(3),
+            RepetitionPolicy::at_least(2).at_most(3),
+            RepetitionPolicy::at_least(2).in_base(2),
+            RepetitionPolicy::exactly(2).in_base(16),
+            RepetitionPolicy::exactly(2).padded_to(4),
+            RepetitionPolicy::at_least(2).padded_to(6).in_base(3),
+        ] {
+            check_totals(policy, 0, 3000);
+            check_totals(policy, 95, 115);
+            check_totals(policy, 998, 1012);
+        }
+        check_totals(RepetitionPolicy::at_least(2).padded_to(3), 0, 200_000);
+
+        // Repunits over u128::MAX, only 0 is padded enough
+        let wide = RepetitionPolicy::at_least(2).padded_to(45);
+        check_totals(wide, 0, 3000);
+        check_totals(wide, 1, 3000);
+        assert_eq!(wide.invalid_ids(0, 10u128.pow(20)), vec![0]);
+        assert_eq!(wide.count(0, 10u128.pow(20)), Some(1));
+    }
+}
@@ -1,33 +1,122 @@
+use std::{
+    fmt::{self, Display},
+    io,
+};
+
+use crate::{
+    input::LineSource,
+    parse::{self, Cursor, ParseResult},
+};
+
+pub mod objective;
+pub mod streaming;
+
+pub use streaming::for_each_bank;
+
+fn bank(c: &mut Cursor) -> ParseResult<Vec<u32>> {
+    c.skip_spaces();
+    c.many1(|c| c.digit())
+}
+
+/// One bank of battery digits per line
+pub fn parse_banks(input: &str) -> ParseResult<Vec<Vec<u32>>> {
+    parse::lines(input, 1, bank)
+}
+
+/// Batteries turned on in a bank, in bank order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub indices: Vec<usize>,
+    pub digits: Vec<u32>,
+}
+
+impl Selection {
+    /// Joltage of the selected batteries, `None` if it does not fit in a `u128`
+    pub fn value(&self) -> Option<u128> {
+        self.digits
+            .iter()
+            .try_fold(0u128, |acc, d| acc.checked_mul(10)?.checked_add(*d as u128))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BankTooShort {
+    pub len: usize,
+    pub wanted: usize,
+}
+
+impl Display for BankTooShort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bank of {} batteries cannot turn on {}",
+            self.len, self.wanted
+        )
+    }
+}
+
+impl std::error::Error for BankTooShort {}
+
+/// Picks the `k` batteries giving the biggest joltage, the earliest ones on ties.
+///
+/// Monotonic stack in O(n): a battery pops the smaller ones before it while there are
+/// still enough batteries left to fill the `k` slots.
+pub fn largest_subsequence(bank: &[u32], k: usize) -> Result<Selection, BankTooShort> {
+    if bank.len() < k {
+        return Err(BankTooShort {
+            len: bank.len(),
+            wanted: k,
+        });
+    }
+
+    let mut can_drop = bank.len() - k;
+    let mut stack: Vec<usize> = Vec::with_capacity(bank.len());
+    for (i, digit) in bank.iter().enumerate() {
+        while can_drop > 0
+            && let Some(&top) = stack.last()
+            && bank[top] < *digit
+        {
+            stack.pop();
+            can_drop -= 1;
+        }
+        stack.push(i);
+    }
+    stack.truncate(k);
+
+    Ok(Selection {
+        digits: stack.iter().map(|i| bank[*i]).collect(),
+        indices: stack,
+    })
+}
+
 pub mod part1 {
+    use super::*;
 
     pub type ParsedInput = Vec<Vec<u32>>;
 
     pub fn parse_input(input: &str) -> Vec<Vec<u32>> {
-        input
-            .trim()
-            .lines()
-            .map(|line| {
-                line.trim()
-                    .chars()
-                    .map(|d| d.to_digit(10).unwrap())
-                    .collect()
-            })
-            .collect()
+        parse_banks(input).unwrap()
+    }
+
+    const BATTERIES: usize = 2;
+
+    fn bank_joltage(bank: &[u32]) -> u32 {
+        largest_subsequence(bank, BATTERIES)
+            .unwrap()
+            .value()
+            .unwrap() as u32
     }
 
     pub fn solution(input: &ParsedInput) -> u32 {
-        input
-            .iter()
-            .map(|bank| {
-                let (max_p, max) = bank[..bank.len() - 1]
-                    .iter()
-                    .enumerate()
-                    .rev()
-                    .max_by_key(|(_p, e)| **e)
-                    .unwrap();
-                *max * 10 + bank[max_p + 1..].iter().max().unwrap()
-            })
-            .sum()
+        input.iter().map(|bank| bank_joltage(bank)).sum()
+    }
+
+    /// Same as [`solution`] with a single pass over every bank, keeping its digits as
+    /// bytes and one bank in memory at a time
+    pub fn solution_streaming(source: impl LineSource) -> io::Result<u32> {
+        let mut sum = 0;
+        streaming::for_each_selection(source, BATTERIES, |s| sum += s.value().unwrap() as u32)?;
+        Ok(sum)
     }
 
     #[cfg(test)]
@@ -63,75 +152,63 @@ pub mod part1 {
             let sol = solution(&input);
             assert_eq!(sol, 17405);
         }
+
+        #[test]
+        fn test_largest_subsequence() {
+            let bank = parse_input("818181911112111").remove(0);
+            let selection = largest_subsequence(&bank, 2).unwrap();
+            assert_eq!(selection.indices, vec![6, 11]);
+            assert_eq!(selection.value(), Some(92));
+            assert_eq!(largest_subsequence(&bank, 15).unwrap().digits, bank);
+            assert_eq!(largest_subsequence(&bank, 0).unwrap().value(), Some(0));
+            assert_eq!(
+                largest_subsequence(&bank, 16),
+                Err(BankTooShort {
+                    len: 15,
+                    wanted: 16
+                })
+            );
+            assert_eq!(largest_subsequence(&[9; 40], 40).unwrap().value(), None);
+        }
+
+        #[test]
+        fn test_solution_streaming() {
+            crate::input::test::check_sources(
+                solution_streaming,
+                (EXAMPLE, 357),
+                ("./src/ed2025/day3/input.txt", 17405),
+            );
+        }
     }
 }
 
 pub mod part2 {
+    use super::*;
 
     pub type ParsedInput = Vec<Vec<u32>>;
 
     pub fn parse_input(input: &str) -> Vec<Vec<u32>> {
-        input
-            .trim()
-            .lines()
-            .map(|line| {
-                line.trim()
-                    .chars()
-                    .map(|d| d.to_digit(10).unwrap())
-                    .collect()
-            })
-            .collect()
+        parse_banks(input).unwrap()
     }
     const MAX_BATTERIES: usize = 12;
 
-    /// Algorithm description:
-    /// for b bank
-    /// 1. Look for the biggest battery on the first index possible on b -> (pos, val)
-    ///    where pos < b.len() - 12 (*1) && pos > last_pos
-    /// 3. Look for the biggest battery on the first index possible on b -> (pos, val)
-    ///    where pos < b.len() - 11 && pos > last_pos
-    ///    ...
-    fn biggest_slice(bank: &[u32]) -> Vec<u32> {
-        let mut res = vec![];
-        let mut last_pos = -1;
-
-        for i in 0..MAX_BATTERIES {
-            let (pos, val) = bank[.. 
-                // (*1)
-                bank.len() - (MAX_BATTERIES - i - 1)]
-                .iter()
-                .enumerate()
-                .filter(|(pos, _)| *pos as i32 > last_pos)
-                .rev()
-                .max_by_key(|(_p, val)| **val)
-                .unwrap();
-
-            res.push(*val);
-            last_pos = pos as i32;
-        }
-
-        res
+    fn bank_joltage(bank: &[u32]) -> u128 {
+        largest_subsequence(bank, MAX_BATTERIES)
+            .unwrap()
+            .value()
+            .unwrap()
     }
 
     pub fn solution(input: &ParsedInput) -> u128 {
-        let mut sum: u128 = 0;
-
-        for bank in input {
-            let bs = biggest_slice(bank);
-
-            let bank_sum: u128 = bs
-                .into_iter()
-                .rev()
-                .enumerate()
-                .map(|(pos, elem)| (elem as u128) * 10u128.pow(pos as u32))
-                .sum();
-
-            dbg!(bank_sum);
-
-            sum += bank_sum;
-        }
+        input.iter().map(|bank| bank_joltage(bank)).sum()
+    }
 
-        sum
+    /// Same as [`solution`] with a single pass over every bank, keeping its digits as
+    /// bytes and one bank in memory at a time
+    pub fn solution_streaming(source: impl LineSource) -> io::Result<u128> {
+        let mut sum = 0;
+        streaming::for_each_selection(source, MAX_BATTERIES, |s| sum += s.value().unwrap())?;
+        Ok(sum)
     }
 
     #[cfg(test)]
@@ -167,5 +244,14 @@ pub mod part2 {
             let sol = solution(&input);
             assert_eq!(sol, 171990312704598);
         }
+
+        #[test]
+        fn test_solution_streaming() {
+            crate::input::test::check_sources(
+                solution_streaming,
+                (EXAMPLE, 3121910778619),
+                ("./src/ed2025/day3/input.txt", 171990312704598),
+            );
+        }
     }
 }
@@ -0,0 +1,263 @@
+use crate::{
+    bigint::BigUint,
+    ed2025::day3::{BankTooShort, Selection, largest_subsequence},
+};
+
+/// What makes a selection of batteries the best one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Objective {
+    Largest,
+    Smallest,
+    /// Largest joltage that is a multiple of the number, which can't be 0
+    LargestDivisibleBy(u32),
+    /// Largest joltage not over the number
+    LargestAtMost(BigUint),
+}
+
+impl Selection {
+    /// Joltage of the selected batteries, whatever the number of them
+    pub fn big_value(&self) -> BigUint {
+        BigUint::from_digits(self.digits.iter().copied())
+    }
+}
+
+fn selection(bank: &[u32], indices: Vec<usize>) -> Selection {
+    Selection {
+        digits: indices.iter().map(|i| bank[*i]).collect(),
+        indices,
+    }
+}
+
+/// Picks `k` batteries of `bank` following `objective`, `Ok(None)` if no selection
+/// meets it.
+pub fn select(
+    bank: &[u32],
+    k: usize,
+    objective: &Objective,
+) -> Result<Option<Selection>, BankTooShort> {
+    if bank.len() < k {
+        return Err(BankTooShort {
+            len: bank.len(),
+            wanted: k,
+        });
+    }
+
+    Ok(match objective {
+        Objective::Largest => Some(largest_subsequence(bank, k)?),
+        Objective::Smallest => Some(smallest_subsequence(bank, k)),
+        Objective::LargestDivisibleBy(m) => largest_divisible_by(bank, k, *m),
+        Objective::LargestAtMost(max) => largest_at_most(bank, k, max),
+    })
+}
+
+/// Same monotonic stack as [`largest_subsequence`] popping the bigger batteries
+fn smallest_subsequence(bank: &[u32], k: usize) -> Selection {
+    let mut can_drop = bank.len() - k;
+    let mut stack: Vec<usize> = Vec::with_capacity(bank.len());
+    for (i, digit) in bank.iter().enumerate() {
+        while can_drop > 0
+            && let Some(&top) = stack.last()
+            && bank[top] > *digit
+        {
+            stack.pop();
+            can_drop -= 1;
+        }
+        stack.push(i);
+    }
+    stack.truncate(k);
+    selection(bank, stack)
+}
+
+/// Greedy from the most significant battery, keeping the biggest one after which
+/// a multiple of `m` can still be completed.
+///
+/// Some `j` batteries of `bank[i..]` make a number `r` modulo `m` for every `i` up to
+/// `last[j][r]`, as a longer suffix can always skip its first battery. Keeping only that
+/// last start takes O(k * m) memory instead of one flag per start.
+fn largest_divisible_by(bank: &[u32], k: usize, m: u32) -> Option<Selection> {
+    assert!(m > 0, "Nothing is divisible by 0");
+    let (n, m) = (bank.len(), m as usize);
+    let idx = |j: usize, r: usize| j * m + r;
+    // 10^j mod m
+    let pow10: Vec<usize> = (0..=k)
+        .scan(1 % m, |p, _| {
+            let current = *p;
+            *p = *p * 10 % m;
+            Some(current)
+        })
+        .collect();
+
+    let mut last: Vec<Option<usize>> = vec![None; (k + 1) * m];
+    last[idx(0, 0)] = Some(n);
+    let reachable = |last: &[Option<usize>], i: usize, j: usize, r: usize| {
+        last[idx(j, r)].is_some_and(|l| i <= l)
+    };
+    for i in (0..n).rev() {
+        for j in 1..=k.min(n - i) {
+            for r in 0..m {
+                // Taking bank[i] as the most significant of the j, skipping it is
+                // already covered by a later start
+                if reachable(&last, i + 1, j - 1, r) {
+                    let with_digit = (bank[i] as usize * pow10[j - 1] + r) % m;
+                    // Starts go down, the first one found is the last
+                    last[idx(j, with_digit)].get_or_insert(i);
+                }
+            }
+        }
+    }
+
+    if !reachable(&last, 0, k, 0) {
+        return None;
+    }
+
+    let mut indices = Vec::with_capacity(k);
+    let (mut from, mut prefix) = (0, 0);
+    for left in (1..=k).rev() {
+        // The rest has to make the prefix a multiple once appended
+        let (index, _) = (from..=n - left)
+            .filter(|i| {
+                let prefix = (prefix * 10 + bank[*i] as usize) % m;
+                let needed = (m - prefix * pow10[left - 1] % m) % m;
+                reachable(&last, i + 1, left - 1, needed)
+            })
+            .map(|i| (i, bank[i]))
+            .rev()
+            .max_by_key(|(_, d)| *d)?;
+        indices.push(index);
+        prefix = (prefix * 10 + bank[index] as usize) % m;
+        from = index + 1;
+    }
+    Some(selection(bank, indices))
+}
+
+/// The answer shares the longest possible prefix with `max`, then has a smaller
+/// battery and the largest possible rest.
+fn largest_at_most(bank: &[u32], k: usize, max: &BigUint) -> Option<Selection> {
+    let max_digits: Vec<u32> = max
+        .to_string()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap())
+        .collect();
+    if max_digits.len() > k {
+        return largest_subsequence(bank, k).ok();
+    }
+    // Same number of digits as the selection
+    let max_digits: Vec<u32> = std::iter::repeat_n(0, k - max_digits.len())
+        .chain(max_digits)
+        .collect();
+
+    // Earliest batteries matching the first digits of max
+    let mut prefix: Vec<usize> = Vec::with_capacity(k);
+    let mut from = 0;
+    for (t, digit) in max_digits.iter().enumerate() {
+        match (from..=bank.len() - (k - t)).find(|i| bank[*i] == *digit) {
+            Some(i) => {
+                prefix.push(i);
+                from = i + 1;
+            }
+            None => break,
+        }
+    }
+    if prefix.len() == k {
+        return Some(selection(bank, prefix));
+    }
+
+    // Longest shared prefix first
+    (0..=prefix.len()).rev().find_map(|t| {
+        let from = if t == 0 { 0 } else { prefix[t - 1] + 1 };
+        let (index, _) = (from..=bank.len() - (k - t))
+            .map(|i| (i, bank[i]))
+            .filter(|(_, d)| *d < max_digits[t])
+            .rev()
+            .max_by_key(|(_, d)| *d)?;
+        let rest = largest_subsequence(&bank[index + 1..], k - t - 1).ok()?;
+
+        let mut indices = prefix[..t].to_vec();
+        indices.push(index);
+        indices.extend(rest.indices.iter().map(|i| i + index + 1));
+        Some(selection(bank, indices))
+    })
+}
+
+/// Joltages of the best selection of every bank added up, banks without a selection
+/// meeting `objective` add nothing.
+pub fn total_joltage(
+    banks: &[Vec<u32>],
+    k: usize,
+    objective: &Objective,
+) -> Result<BigUint, BankTooShort> {
+    let mut total = BigUint::zero();
+    for bank in banks {
+        if let Some(selection) = select(bank, k, objective)? {
+            total += &selection.big_value();
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day3::parse_banks;
+
+    const EXAMPLE: &str = "987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
+
+    /// Every way of picking `k` of `bank`, for checking against
+    fn all_selections(bank: &[u32], k: usize) -> Vec<BigUint> {
+        (0u32..1 << bank.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                BigUint::from_digits(
+                    (0..bank.len())
+                        .filter(|i| mask >> i & 1 == 1)
+                        .map(|i| bank[i]),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_objectives_against_brute_force() {
+        let banks = parse_banks("3141592653589\n2718281828459\n1001001\n").unwrap();
+        for bank in &banks {
+            for k in [1, 3, 5] {
+                let all = all_selections(bank, k);
+                let value = |objective| select(bank, k, &objective).unwrap().map(|s| s.big_value());
+
+                assert_eq!(value(Objective::Smallest), all.iter().min().cloned());
+                assert_eq!(value(Objective::Largest), all.iter().max().cloned());
+                for m in [1, 7, 13, 64] {
+                    let expected = all.iter().filter(|v| v.rem_small(m) == 0).max();
+                    assert_eq!(value(Objective::LargestDivisibleBy(m)), expected.cloned());
+                }
+                for max in [0u32, 9, 500, 31415, 99999] {
+                    let max = BigUint::from(max);
+                    let expected = all.iter().filter(|v| **v <= max).max();
+                    assert_eq!(value(Objective::LargestAtMost(max)), expected.cloned());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_total_joltage() {
+        let banks = parse_banks(EXAMPLE).unwrap();
+        assert_eq!(
+            total_joltage(&banks, 12, &Objective::Largest).unwrap(),
+            BigUint::from(3121910778619u64)
+        );
+
+        let long_bank = vec![vec![9; 60]];
+        assert_eq!(
+            total_joltage(&long_bank, 50, &Objective::Largest)
+                .unwrap()
+                .to_string(),
+            "9".repeat(50)
+        );
+        assert!(total_joltage(&banks, 16, &Objective::Smallest).is_err());
+    }
+}
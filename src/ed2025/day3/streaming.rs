@@ -0,0 +1,283 @@
+//! Selecting batteries while the bank is still being read.
+//!
+//! [`largest_subsequence`](super::largest_subsequence) needs the length of the bank up
+//! front to know how many batteries it can drop. Holding back the last `k` batteries
+//! gets around it: when a battery leaves that window at least `k` come after it, so it
+//! can pop any smaller battery. Only the batteries of the window are placed once the
+//! end of the bank is known. Memory is O(k) whatever the length of the bank.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+};
+
+use crate::{
+    ed2025::day3::{BankTooShort, Selection},
+    input::{LineSource, invalid_data},
+    parse::ParseError,
+};
+
+/// Largest `k` battery selection of a bank given one battery at a time
+#[derive(Debug, Clone)]
+pub struct Selector {
+    k: usize,
+    seen: usize,
+    /// Last `k` batteries, placed once it is known how many come after them
+    pending: VecDeque<(usize, u8)>,
+    /// Index and digit of the batteries kept, never more than `k`
+    stack: Vec<(usize, u8)>,
+}
+
+impl Selector {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            seen: 0,
+            pending: VecDeque::with_capacity(k + 1),
+            stack: Vec::with_capacity(k),
+        }
+    }
+
+    /// Batteries pushed since the last [`Self::finish`]
+    pub fn len(&self) -> usize {
+        self.seen
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen == 0
+    }
+
+    /// Adds the next battery of the bank, panics if `digit` is bigger than 9.
+    pub fn push(&mut self, digit: u8) {
+        assert!(digit < 10, "{digit} is not a decimal digit");
+        self.pending.push_back((self.seen, digit));
+        self.seen += 1;
+        if self.pending.len() > self.k {
+            let battery = self.pending.pop_front().unwrap();
+            self.place(battery, self.k + 1);
+        }
+    }
+
+    /// `left` batteries from this one to the end of the bank, this one included
+    fn place(&mut self, (index, digit): (usize, u8), left: usize) {
+        while let Some(&(_, top)) = self.stack.last()
+            && top < digit
+            && self.stack.len() - 1 + left >= self.k
+        {
+            self.stack.pop();
+        }
+        if self.stack.len() < self.k {
+            self.stack.push((index, digit));
+        }
+    }
+
+    /// Selection of the bank pushed so far, the selector is then ready for the next one.
+    pub fn finish(&mut self) -> Result<Selection, BankTooShort> {
+        let mut left = self.pending.len();
+        while let Some(battery) = self.pending.pop_front() {
+            self.place(battery, left);
+            left -= 1;
+        }
+        let len = std::mem::take(&mut self.seen);
+        let (indices, digits) = self.stack.drain(..).map(|(i, d)| (i, d as u32)).unzip();
+        if len < self.k {
+            return Err(BankTooShort {
+                len,
+                wanted: self.k,
+            });
+        }
+        Ok(Selection { indices, digits })
+    }
+}
+
+fn error(line: usize, column: usize, message: String) -> io::Error {
+    invalid_data(ParseError {
+        line,
+        column,
+        message,
+    })
+}
+
+/// Bytes of the line being read, blanks are only allowed around the batteries as
+/// [`parse_banks`](super::parse_banks) does.
+#[derive(Debug, Default)]
+struct BankLine {
+    started: bool,
+    /// Column of the first blank after a battery, wrong if another battery follows
+    blank: Option<usize>,
+}
+
+impl BankLine {
+    /// Digit of a battery, `None` for the blanks around them
+    fn battery(&mut self, byte: u8, line: usize, column: usize) -> io::Result<Option<u8>> {
+        match byte {
+            b'0'..=b'9' => match self.blank {
+                Some(blank) => Err(error(line, blank, "Blank between batteries".to_string())),
+                None => {
+                    self.started = true;
+                    Ok(Some(byte - b'0'))
+                }
+            },
+            b' ' | b'\t' | b'\r' => {
+                if self.started {
+                    self.blank.get_or_insert(column);
+                }
+                Ok(None)
+            }
+            _ => Err(error(
+                line,
+                column,
+                format!("Expected a digit, found {:?}", byte as char),
+            )),
+        }
+    }
+}
+
+fn finish_bank(selector: &mut Selector, f: &mut impl FnMut(Selection)) -> io::Result<()> {
+    if !selector.is_empty() {
+        f(selector.finish().map_err(invalid_data)?);
+    }
+    Ok(())
+}
+
+/// Feeds every non blank line of `source` as a bank to `f`, one at a time, read with
+/// the same byte digits as [`for_each_selection`].
+pub fn for_each_bank(source: impl LineSource, mut f: impl FnMut(&[u32])) -> io::Result<()> {
+    let mut bank = vec![];
+    source.try_for_each_line(|line_number, line| {
+        bank.clear();
+        let mut bank_line = BankLine::default();
+        for (i, byte) in line.bytes().enumerate() {
+            if let Some(digit) = bank_line.battery(byte, line_number, i + 1)? {
+                bank.push(digit as u32);
+            }
+        }
+        if !bank.is_empty() {
+            f(&bank);
+        }
+        Ok(())
+    })
+}
+
+/// Largest `k` battery selection of every non blank line of `source`, given to `f`.
+pub fn for_each_selection(
+    source: impl LineSource,
+    k: usize,
+    mut f: impl FnMut(Selection),
+) -> io::Result<()> {
+    let mut selector = Selector::new(k);
+    source.try_for_each_line(|line_number, line| {
+        let mut bank_line = BankLine::default();
+        for (i, byte) in line.bytes().enumerate() {
+            if let Some(digit) = bank_line.battery(byte, line_number, i + 1)? {
+                selector.push(digit);
+            }
+        }
+        finish_bank(&mut selector, &mut f)
+    })
+}
+
+/// Same as [`for_each_selection`] reading `reader` a chunk at a time instead of a line
+/// at a time, so banks longer than the memory available can be solved.
+pub fn read_selections(
+    mut reader: impl BufRead,
+    k: usize,
+    mut f: impl FnMut(Selection),
+) -> io::Result<()> {
+    let mut selector = Selector::new(k);
+    let (mut line, mut column) = (1, 0);
+    let mut bank_line = BankLine::default();
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return finish_bank(&mut selector, &mut f);
+        }
+        for byte in chunk {
+            column += 1;
+            if *byte == b'\n' {
+                finish_bank(&mut selector, &mut f)?;
+                line += 1;
+                column = 0;
+                bank_line = BankLine::default();
+            } else if let Some(digit) = bank_line.battery(*byte, line, column)? {
+                selector.push(digit);
+            }
+        }
+        let read = chunk.len();
+        reader.consume(read);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+    use crate::ed2025::day3::{largest_subsequence, parse_banks};
+
+    #[test]
+    fn test_selector_matches_largest_subsequence() {
+        let banks = parse_banks(
+            "987654321111111\n811111111111119\n234234234234278\n818181911112111\n\
+             3141592653589793238462643383279\n1\n11111\n90909090\n",
+        )
+        .unwrap();
+        for k in 0..=32 {
+            // Reused from one bank to the next, even after an error
+            let mut selector = Selector::new(k);
+            for bank in &banks {
+                bank.iter().for_each(|d| selector.push(*d as u8));
+                assert_eq!(selector.len(), bank.len());
+                assert_eq!(
+                    selector.finish(),
+                    largest_subsequence(bank, k),
+                    "{bank:?} k = {k}"
+                );
+                assert!(selector.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_selections() {
+        let mut values = vec![];
+        // Chunks end in the middle of the banks
+        let reader =
+            BufReader::with_capacity(4, "987654321111111\n\n 811111111111119\r\n".as_bytes());
+        read_selections(reader, 2, |s| values.push(s.value().unwrap())).unwrap();
+        assert_eq!(values, vec![98, 89]);
+
+        // Never held in memory
+        let long_bank = io::repeat(b'1')
+            .take(5_000_000)
+            .chain("9".as_bytes())
+            .chain(io::repeat(b'2').take(3_000_000));
+        let mut selections = vec![];
+        read_selections(BufReader::new(long_bank), 12, |s| selections.push(s)).unwrap();
+        assert_eq!(selections[0].value(), Some(922_222_222_222));
+        assert_eq!(selections[0].indices[0], 5_000_000);
+
+        let err = read_selections("12\n3x4\n".as_bytes(), 1, |_| ()).unwrap_err();
+        assert_eq!(err.to_string(), "2:2: Expected a digit, found 'x'");
+        assert!(for_each_selection("123\n4\n", 2, |_| ()).is_err());
+
+        let input = "987654321111111\n\n 811111111111119\r\n";
+        let mut banks = vec![];
+        for_each_bank(input, |bank| banks.push(bank.to_vec())).unwrap();
+        assert_eq!(banks, parse_banks(input).unwrap());
+        assert!(for_each_bank("12\n3x4\n", |_| ()).is_err());
+
+        // Blanks only around the batteries, for every reader
+        let spaced = "12\n 34 \t\n56 78\n";
+        assert!(parse_banks(spaced).is_err());
+        let err = for_each_bank(spaced, |_| ()).unwrap_err();
+        assert_eq!(err.to_string(), "3:3: Blank between batteries");
+        let err = for_each_selection(spaced, 1, |_| ()).unwrap_err();
+        assert_eq!(err.to_string(), "3:3: Blank between batteries");
+        let reader = BufReader::with_capacity(4, spaced.as_bytes());
+        let err = read_selections(reader, 1, |_| ()).unwrap_err();
+        assert_eq!(err.to_string(), "3:3: Blank between batteries");
+        assert!(parse_banks("12\n 34 \t\n").is_ok());
+        assert!(for_each_bank("12\n 34 \t\n", |_| ()).is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+//! Frames of the erosion of [`part2::rounds`](super::part2::rounds), as text or as
+//! PPM images.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ed2025::day4::{Grid, GridItem},
+    grid,
+};
+
+/// How a cell looks in a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Roll,
+    /// Roll taken out by the round the frame shows
+    Removed,
+}
+
+impl From<GridItem> for Cell {
+    fn from(value: GridItem) -> Self {
+        match value {
+            GridItem::Empty => Cell::Empty,
+            GridItem::Roll => Cell::Roll,
+        }
+    }
+}
+
+impl From<Cell> for char {
+    fn from(value: Cell) -> Self {
+        match value {
+            Cell::Empty => '.',
+            Cell::Roll => '@',
+            Cell::Removed => 'x',
+        }
+    }
+}
+
+impl Cell {
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Cell::Empty => [0x20, 0x20, 0x20],
+            Cell::Roll => [0xf0, 0xe6, 0xd2],
+            Cell::Removed => [0xd0, 0x30, 0x30],
+        }
+    }
+}
+
+pub type Frame = grid::Grid<Cell>;
+
+/// `initial` as it is, then one frame per round with its rolls marked as removed, then
+/// the rolls left.
+pub fn frames(initial: &Grid, rounds: &[Vec<(usize, usize)>]) -> Vec<Frame> {
+    let mut current = initial.map(|item| Cell::from(*item));
+    let mut frames = vec![current.clone()];
+    for round in rounds {
+        for pos in round {
+            current[*pos] = Cell::Removed;
+        }
+        frames.push(current.clone());
+        for pos in round {
+            current[*pos] = Cell::Empty;
+        }
+    }
+    if !rounds.is_empty() {
+        frames.push(current);
+    }
+    frames
+}
+
+/// Every frame rendered with the puzzle's characters, `x` for the removed rolls.
+pub fn text_frames(frames: &[Frame]) -> Vec<String> {
+    frames
+        .iter()
+        .map(|frame| frame.render(|cell| char::from(*cell)))
+        .collect()
+}
+
+/// Binary PPM image of `frame`, every cell a `scale` by `scale` square.
+pub fn write_ppm(frame: &Frame, scale: usize, mut out: impl Write) -> io::Result<()> {
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        frame.cols() * scale,
+        frame.rows() * scale
+    )?;
+    let mut line = Vec::with_capacity(frame.cols() * scale * 3);
+    for row in frame.iter_rows() {
+        line.clear();
+        for cell in row {
+            for _ in 0..scale {
+                line.extend_from_slice(&cell.rgb());
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    out.flush()
+}
+
+/// Writes every frame to `dir` as `frame_0000.ppm`, `frame_0001.ppm`... and returns
+/// their paths. `dir` has to exist.
+pub fn write_ppm_sequence(
+    frames: &[Frame],
+    scale: usize,
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.as_ref().join(format!("frame_{i:04}.ppm"));
+            write_ppm(frame, scale, BufWriter::new(File::create(&path)?))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day4::{EXAMPLE, parse_input, part2};
+
+    #[test]
+    fn test_frames() {
+        let initial = parse_input(EXAMPLE);
+        let mut eroded = initial.clone();
+        let rounds = part2::rounds(&mut eroded);
+        let text = text_frames(&frames(&initial, &rounds));
+
+        assert_eq!(text.len(), rounds.len() + 2);
+        assert_eq!(text[0], initial.render(|item| char::from(*item)));
+        assert_eq!(
+            text[1],
+            "..xx.xx@x.
+x@@.@.@.@@
+@@@@@.x.@@
+@.@@@@..@.
+x@.@@@@.@x
+.@@@@@@@.@
+.@.@.@.@@@
+x.@@@.@@@@
+.@@@@@@@@.
+x.x.@@@.x.
+"
+        );
+        assert_eq!(
+            *text.last().unwrap(),
+            eroded.render(|item| char::from(*item))
+        );
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let frame = Frame::from_rows(vec![vec![Cell::Roll, Cell::Removed]]).unwrap();
+        let mut out = vec![];
+        write_ppm(&frame, 2, &mut out).unwrap();
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(&pixels[..3], &Cell::Roll.rgb());
+        assert_eq!(&pixels[pixels.len() - 3..], &Cell::Removed.rgb());
+    }
+}
@@ -0,0 +1,293 @@
+//! Day 4 as a cellular automaton: which cells count as neighbours, what happens at the
+//! edges and how the rolls around a cell decide its next state.
+
+use crate::ed2025::day4::{ACCESSIBLE_BELOW, Grid, GridItem};
+
+/// Cells around a cell, as (row, col) offsets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// At most `radius` orthogonal steps away, the 4 orthogonal cells for 1
+    VonNeumann { radius: usize },
+    /// In the square of side `2 * radius + 1` around, the 8 surrounding cells for 1
+    Moore { radius: usize },
+    /// Any offsets, they don't need to be symmetric
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighbourhood {
+    /// Offsets in row-major order, without the cell itself unless it is custom
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let square = |radius: usize| {
+            let r = radius as isize;
+            (-r..=r)
+                .flat_map(move |dr| (-r..=r).map(move |dc| (dr, dc)))
+                .filter(|d| *d != (0, 0))
+        };
+        match self {
+            Neighbourhood::VonNeumann { radius } => square(*radius)
+                .filter(|(dr, dc)| dr.unsigned_abs() + dc.unsigned_abs() <= *radius)
+                .collect(),
+            Neighbourhood::Moore { radius } => square(*radius).collect(),
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+/// What is past the edges of the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Nothing, cells near the edges have fewer neighbours
+    Bounded,
+    /// The other side, the grid wraps around like a torus
+    Toroidal,
+}
+
+/// Next state of a cell from its current one and the rolls around it
+pub trait Rule {
+    fn apply(&self, item: GridItem, rolls_around: usize) -> GridItem;
+}
+
+impl<F: Fn(GridItem, usize) -> GridItem> Rule for F {
+    fn apply(&self, item: GridItem, rolls_around: usize) -> GridItem {
+        self(item, rolls_around)
+    }
+}
+
+/// Rolls with fewer rolls than this around them are removed, nothing ever appears.
+/// The puzzle is `Threshold(4)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Threshold(pub usize);
+
+impl Rule for Threshold {
+    fn apply(&self, item: GridItem, rolls_around: usize) -> GridItem {
+        if item == GridItem::Roll && rolls_around < self.0 {
+            GridItem::Empty
+        } else {
+            item
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<R> {
+    offsets: Vec<(isize, isize)>,
+    topology: Topology,
+    rule: R,
+}
+
+impl Automaton<Threshold> {
+    /// 8 neighbours and no wrapping, rolls with fewer than `threshold` around removed
+    pub fn moore(threshold: usize) -> Self {
+        Self::new(
+            &Neighbourhood::Moore { radius: 1 },
+            Topology::Bounded,
+            Threshold(threshold),
+        )
+    }
+
+    /// 8 neighbours, no wrapping and rolls with fewer than 4 around removed
+    pub fn puzzle() -> Self {
+        Self::moore(ACCESSIBLE_BELOW)
+    }
+
+    /// Rolls removed by every step until nothing changes, `grid` is left with the rolls
+    /// that stay. Same as stepping with [`Self::next`], in O(cells * neighbours).
+    ///
+    /// Worklist instead of rescanning the grid: every roll keeps its count of rolls
+    /// around, and removing one only updates the cells that have it as a neighbour. A
+    /// roll joins the next step when it first goes below the threshold.
+    pub fn erosion_rounds(&self, grid: &mut Grid) -> Vec<Vec<(usize, usize)>> {
+        let threshold = self.rule.0;
+        let mut around = grid.map(|_| 0);
+        let mut round = Vec::new();
+        for (pos, item) in grid.iter() {
+            if *item == GridItem::Roll {
+                around[pos] = self.rolls_around(grid, pos);
+                if around[pos] < threshold {
+                    round.push(pos);
+                }
+            }
+        }
+
+        let reversed: Vec<_> = self.offsets.iter().map(|(dr, dc)| (-dr, -dc)).collect();
+        let mut rounds = Vec::new();
+        while !round.is_empty() {
+            grid.remove_rolls(&round);
+            let mut next = Vec::new();
+            for pos in &round {
+                for affected in self.offset_all(grid, *pos, &reversed) {
+                    if grid[affected] == GridItem::Roll {
+                        around[affected] -= 1;
+                        // Rolls already below were in an earlier round
+                        if around[affected] == threshold - 1 {
+                            next.push(affected);
+                        }
+                    }
+                }
+            }
+            rounds.push(std::mem::replace(&mut round, next));
+        }
+        rounds
+    }
+}
+
+impl<R: Rule> Automaton<R> {
+    pub fn new(neighbourhood: &Neighbourhood, topology: Topology, rule: R) -> Self {
+        Self {
+            offsets: neighbourhood.offsets(),
+            topology,
+            rule,
+        }
+    }
+
+    fn offset_all<'a>(
+        &self,
+        grid: &'a Grid,
+        (row, col): (usize, usize),
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let topology = self.topology;
+        offsets.iter().filter_map(move |delta| match topology {
+            Topology::Bounded => grid.offset((row, col), *delta),
+            Topology::Toroidal => Some((
+                (row as isize + delta.0).rem_euclid(grid.rows() as isize) as usize,
+                (col as isize + delta.1).rem_euclid(grid.cols() as isize) as usize,
+            )),
+        })
+    }
+
+    /// Neighbours of `pos`, once per offset reaching them, which can be more than once
+    /// on a small toroidal grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        grid: &'a Grid,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.offset_all(grid, pos, &self.offsets)
+    }
+
+    pub fn rolls_around(&self, grid: &Grid, pos: (usize, usize)) -> usize {
+        self.neighbours(grid, pos)
+            .filter(|p| grid[*p] == GridItem::Roll)
+            .count()
+    }
+
+    /// Cells that change in the next step and their new state
+    pub fn changes(&self, grid: &Grid) -> Vec<((usize, usize), GridItem)> {
+        grid.iter()
+            .filter_map(|(pos, item)| {
+                let next = self.rule.apply(*item, self.rolls_around(grid, pos));
+                (next != *item).then_some((pos, next))
+            })
+            .collect()
+    }
+
+    /// Grid after one step, every cell updated at once
+    pub fn next(&self, grid: &Grid) -> Grid {
+        let mut next = grid.clone();
+        for (pos, item) in self.changes(grid) {
+            next[pos] = item;
+        }
+        next
+    }
+
+    /// Steps until nothing changes or `max_steps` were taken, returning how many steps
+    /// changed something.
+    pub fn run(&self, grid: &mut Grid, max_steps: usize) -> usize {
+        for step in 0..max_steps {
+            let changes = self.changes(grid);
+            if changes.is_empty() {
+                return step;
+            }
+            for (pos, item) in changes {
+                grid[pos] = item;
+            }
+        }
+        max_steps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ed2025::day4::{EXAMPLE, parse_input},
+        grid::{NEIGHBOURS_4, NEIGHBOURS_8},
+    };
+
+    #[test]
+    fn test_neighbourhoods() {
+        assert_eq!(
+            Neighbourhood::VonNeumann { radius: 1 }.offsets(),
+            NEIGHBOURS_4.to_vec()
+        );
+        assert_eq!(
+            Neighbourhood::Moore { radius: 1 }.offsets(),
+            NEIGHBOURS_8.to_vec()
+        );
+        assert_eq!(Neighbourhood::VonNeumann { radius: 2 }.offsets().len(), 12);
+        assert_eq!(Neighbourhood::Moore { radius: 2 }.offsets().len(), 24);
+
+        let grid = parse_input("@..\n...\n..@");
+        let moore =
+            |topology| Automaton::new(&Neighbourhood::Moore { radius: 1 }, topology, Threshold(4));
+        assert_eq!(moore(Topology::Bounded).rolls_around(&grid, (0, 0)), 0);
+        assert_eq!(moore(Topology::Toroidal).rolls_around(&grid, (0, 0)), 1);
+        assert_eq!(
+            moore(Topology::Toroidal).neighbours(&grid, (1, 1)).count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_erosion_rounds_match_stepping() {
+        let neighbourhoods = [
+            Neighbourhood::Moore { radius: 1 },
+            Neighbourhood::VonNeumann { radius: 2 },
+            Neighbourhood::Custom(vec![(0, 1), (1, 1), (2, 0), (-1, -3)]),
+        ];
+        for neighbourhood in &neighbourhoods {
+            for topology in [Topology::Bounded, Topology::Toroidal] {
+                for threshold in [2, 4, 6] {
+                    let automaton = Automaton::new(neighbourhood, topology, Threshold(threshold));
+                    let mut eroded = parse_input(EXAMPLE);
+                    let rounds = automaton.erosion_rounds(&mut eroded);
+
+                    let mut stepped = parse_input(EXAMPLE);
+                    for round in &rounds {
+                        let mut removed: Vec<_> = automaton
+                            .changes(&stepped)
+                            .into_iter()
+                            .map(|(p, _)| p)
+                            .collect();
+                        let mut round = round.clone();
+                        removed.sort();
+                        round.sort();
+                        assert_eq!(removed, round, "{neighbourhood:?} {topology:?} {threshold}");
+                        stepped = automaton.next(&stepped);
+                    }
+                    assert_eq!(stepped, eroded);
+                    assert!(automaton.changes(&stepped).is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        // Game of life blinker
+        let life = |item: GridItem, around: usize| match (item, around) {
+            (GridItem::Roll, 2 | 3) | (GridItem::Empty, 3) => GridItem::Roll,
+            _ => GridItem::Empty,
+        };
+        let automaton =
+            Automaton::new(&Neighbourhood::Moore { radius: 1 }, Topology::Bounded, life);
+        let blinker = parse_input(".....\n..@..\n..@..\n..@..\n.....");
+        let turned = automaton.next(&blinker);
+        assert_eq!(turned, parse_input(".....\n.....\n.@@@.\n.....\n....."));
+        assert_eq!(automaton.next(&turned), blinker);
+
+        let mut grid = blinker.clone();
+        assert_eq!(automaton.run(&mut grid, 5), 5);
+    }
+}
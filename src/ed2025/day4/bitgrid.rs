@@ -0,0 +1,315 @@
+//! Grid of rolls packed 64 cells to a word, for grids too big for one enum per cell.
+//!
+//! The rolls around the cells of a word are counted all at once: the 8 neighbours of
+//! every bit are the words above, below and the row itself shifted by one column, and
+//! they are added with a bit-sliced adder, one `u64` per bit of the counts.
+
+use std::str::FromStr;
+
+use crate::{
+    ed2025::day4::{Grid, GridItem, RollGrid},
+    parse::{self, Cursor, ParseError, ParseResult},
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Rows of bits, column `c` is bit `c % 64` of word `c / 64` and bits past the last
+/// column are always 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    bits: Vec<u64>,
+}
+
+/// Cells of `row` with the one on their left at their place
+fn shifted_from_west(row: &[u64], i: usize) -> u64 {
+    let carry = if i > 0 {
+        row[i - 1] >> (WORD_BITS - 1)
+    } else {
+        0
+    };
+    (row[i] << 1) | carry
+}
+
+/// Cells of `row` with the one on their right at their place
+fn shifted_from_east(row: &[u64], i: usize) -> u64 {
+    let carry = row.get(i + 1).map_or(0, |w| w << (WORD_BITS - 1));
+    (row[i] >> 1) | carry
+}
+
+/// Adds a bit to every count, `counts[b]` holding bit `b` of the 64 counts
+fn add(counts: &mut [u64; 4], bits: u64) {
+    let mut carry = bits;
+    for plane in counts.iter_mut() {
+        let sum = *plane ^ carry;
+        carry &= *plane;
+        *plane = sum;
+    }
+}
+
+/// Bits whose count is lower than `n`, comparing from the most significant bit
+fn less_than(counts: &[u64; 4], n: usize) -> u64 {
+    if n >= 1 << counts.len() {
+        return !0;
+    }
+    let (mut less, mut equal) = (0, !0);
+    for (bit, plane) in counts.iter().enumerate().rev() {
+        if n >> bit & 1 == 1 {
+            less |= equal & !plane;
+            equal &= plane;
+        } else {
+            equal &= !plane;
+        }
+    }
+    less
+}
+
+impl BitGrid {
+    /// Grid without rolls
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words = cols.div_ceil(WORD_BITS);
+        Self {
+            rows,
+            cols,
+            words,
+            bits: vec![0; rows * words],
+        }
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.bits[row * self.words..(row + 1) * self.words]
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> bool {
+        assert!(col < self.cols, "Column {col} out of bounds");
+        self.row(row)[col / WORD_BITS] >> (col % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, (row, col): (usize, usize), roll: bool) {
+        assert!(col < self.cols, "Column {col} out of bounds");
+        let word = &mut self.bits[row * self.words + col / WORD_BITS];
+        let mask = 1 << (col % WORD_BITS);
+        if roll {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Rolls of word `i` of `row` with fewer than `threshold` rolls around them
+    fn accessible_word(&self, row: usize, i: usize, threshold: usize) -> u64 {
+        let mut counts = [0; 4];
+        for r in row.saturating_sub(1)..(row + 2).min(self.rows) {
+            let cells = self.row(r);
+            add(&mut counts, shifted_from_west(cells, i));
+            add(&mut counts, shifted_from_east(cells, i));
+            if r != row {
+                add(&mut counts, cells[i]);
+            }
+        }
+        less_than(&counts, threshold) & self.row(row)[i]
+    }
+
+    /// Every accessible roll, in the layout of `bits`
+    fn accessible(&self, threshold: usize) -> Vec<u64> {
+        (0..self.rows)
+            .flat_map(|row| (0..self.words).map(move |i| self.accessible_word(row, i, threshold)))
+            .collect()
+    }
+
+    pub fn to_grid(&self) -> Grid {
+        Grid::from_vec(
+            self.rows,
+            self.cols,
+            (0..self.rows)
+                .flat_map(|r| (0..self.cols).map(move |c| (r, c)))
+                .map(|pos| match self.get(pos) {
+                    true => GridItem::Roll,
+                    false => GridItem::Empty,
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+}
+
+impl From<&Grid> for BitGrid {
+    fn from(grid: &Grid) -> Self {
+        let mut bits = BitGrid::new(grid.rows(), grid.cols());
+        for (pos, item) in grid.iter() {
+            bits.set(pos, *item == GridItem::Roll);
+        }
+        bits
+    }
+}
+
+/// Same input as [`parse_input`](super::parse_input), rows trimmed, packed row by row
+/// without building a [`Grid`] first
+impl FromStr for BitGrid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<Self> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+            .skip_while(|(_, line)| line.trim().is_empty())
+            .peekable();
+        let Some((_, first)) = lines.peek() else {
+            return Err(Cursor::new(s).error("Expected a grid"));
+        };
+
+        let mut grid = BitGrid::new(0, first.trim().chars().count());
+        while let Some((line_number, row)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            let start = grid.bits.len();
+            grid.bits.resize(start + grid.words, 0);
+            parse::line(row, line_number, |c| {
+                c.take_while(char::is_whitespace);
+                let mut col = 0;
+                while let Some(ch) = c.peek().filter(|ch| !ch.is_whitespace()) {
+                    let item = GridItem::try_from(ch).map_err(|e| c.error(e))?;
+                    if item == GridItem::Roll && col < grid.cols {
+                        grid.bits[start + col / WORD_BITS] |= 1 << (col % WORD_BITS);
+                    }
+                    c.next_char();
+                    col += 1;
+                }
+                match col == grid.cols {
+                    true => Ok(()),
+                    false => {
+                        Err(c.error(format!("Row has {col} elements, expected {}", grid.cols)))
+                    }
+                }
+            })?;
+            grid.rows += 1;
+        }
+
+        match lines.find(|(_, line)| !line.trim().is_empty()) {
+            Some((line_number, line)) => {
+                Err(Cursor::at_line(line, line_number).error(format!("Unexpected \"{line}\"")))
+            }
+            None => Ok(grid),
+        }
+    }
+}
+
+impl RollGrid for BitGrid {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn is_roll(&self, pos: (usize, usize)) -> bool {
+        self.get(pos)
+    }
+
+    fn count_rolls(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn count_accessible(&self, threshold: usize) -> usize {
+        (0..self.rows)
+            .flat_map(|row| (0..self.words).map(move |i| (row, i)))
+            .map(|(row, i)| self.accessible_word(row, i, threshold).count_ones() as usize)
+            .sum()
+    }
+
+    fn remove_accessible(&mut self, threshold: usize) -> usize {
+        let accessible = self.accessible(threshold);
+        let mut removed = 0;
+        for (word, gone) in self.bits.iter_mut().zip(accessible) {
+            *word &= !gone;
+            removed += gone.count_ones() as usize;
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day4::{EXAMPLE, parse_input, part1, part2};
+
+    /// Roughly `percent` of the cells are rolls
+    fn generated(rows: usize, cols: usize, percent: u64) -> Grid {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let cells = (0..rows * cols)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                match state % 100 < percent {
+                    true => GridItem::Roll,
+                    false => GridItem::Empty,
+                }
+            })
+            .collect();
+        Grid::from_vec(rows, cols, cells).unwrap()
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut counts = [0; 4];
+        for _ in 0..5 {
+            add(&mut counts, 0b10);
+        }
+        assert_eq!(counts, [0b10, 0, 0b10, 0]);
+        assert_eq!(less_than(&counts, 5), !0b10);
+        assert_eq!(less_than(&counts, 6), !0);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            EXAMPLE.parse::<BitGrid>(),
+            Ok(BitGrid::from(&parse_input(EXAMPLE)))
+        );
+
+        let grid = generated(5, 150, 40);
+        let text: String = (0..grid.rows())
+            .map(|r| {
+                let row: String = (0..grid.cols()).map(|c| char::from(grid[(r, c)])).collect();
+                row + "\n"
+            })
+            .collect();
+        let bits: BitGrid = format!("\n{text}\n").parse().unwrap();
+        assert_eq!(bits.to_grid(), grid);
+        let indented: String = text.lines().map(|l| format!(" {l}\t\n")).collect();
+        assert_eq!(indented.parse(), Ok(bits));
+
+        let err = "@.\n.@@\n".parse::<BitGrid>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        let err = "@.\n.x\n".parse::<BitGrid>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!("@.\n\n.@".parse::<BitGrid>().unwrap_err().line, 3);
+        assert!("\n \n".parse::<BitGrid>().is_err());
+    }
+
+    #[test]
+    fn test_matches_grid() {
+        let example = parse_input(EXAMPLE);
+        assert_eq!(part1::solve(&BitGrid::from(&example)), 13);
+        assert_eq!(part2::solve(&mut BitGrid::from(&example)), 43);
+
+        for (rows, cols) in [(1, 1), (3, 64), (70, 130), (65, 200)] {
+            let mut grid = generated(rows, cols, 70);
+            let mut bits = BitGrid::from(&grid);
+            assert_eq!(bits.to_grid(), grid);
+            assert_eq!(bits.count_rolls(), grid.count_rolls());
+            for threshold in [3, 4, 5] {
+                assert_eq!(
+                    bits.count_accessible(threshold),
+                    grid.count_accessible(threshold)
+                );
+            }
+
+            assert_eq!(part2::solve(&mut bits), part2::solve(&mut grid));
+            assert_eq!(bits.to_grid(), grid, "{rows}x{cols}");
+        }
+    }
+}
@@ -1,4 +1,11 @@
 use std::str::FromStr;
+
+use crate::{ed2025::day4::automaton::Automaton, grid};
+
+pub mod animation;
+pub mod automaton;
+pub mod bitgrid;
+
 pub const EXAMPLE: &str = "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
@@ -10,108 +17,145 @@ pub const EXAMPLE: &str = "..@@.@@@@.
 .@@@@@@@@.
 @.@.@@@.@.";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GridItem {
     Roll,
     Empty,
 }
 
+impl TryFrom<char> for GridItem {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Self::Empty),
+            '@' => Ok(Self::Roll),
+            _ => Err(format!("Invalid char: {c}")),
+        }
+    }
+}
+
 impl FromStr for GridItem {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "." => Ok(Self::Empty),
-            "@" => Ok(Self::Roll),
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::try_from(c),
             _ => Err(format!("Invalid str: {s}")),
         }
     }
 }
 
-pub struct Grid {
-    pub mat: Vec<Vec<GridItem>>,
-    pub columns: usize,
+impl From<GridItem> for char {
+    fn from(value: GridItem) -> Self {
+        match value {
+            GridItem::Empty => '.',
+            GridItem::Roll => '@',
+        }
+    }
 }
 
-impl Grid {
-    pub fn new(mat: Vec<Vec<GridItem>>) -> Self {
-        let columns = if mat.iter().all(|r| r.len() == mat[0].len()) {
-            mat[0].len()
-        } else {
-            panic!("Some row has diff num of elements")
-        };
-        Self { mat, columns }
-    }
+pub type Grid = grid::Grid<GridItem>;
 
+impl Grid {
     pub fn remove_rolls(&mut self, to_remove: &[(usize, usize)]) {
-        for (i, j) in to_remove.iter() {
-            self.mat[*i][*j] = GridItem::Empty
+        for pos in to_remove.iter() {
+            self[*pos] = GridItem::Empty
         }
     }
 
     pub fn n_adjacent_rolls(&self, row: usize, col: usize) -> usize {
-        let mut ret = 0;
-
-        for i in [-1, 0, 1] {
-            for j in [-1, 1] {
-                let i: i32 = row as i32 + i;
-                let j: i32 = col as i32 + j;
-                if i >= 0
-                    && i < self.mat.len() as i32
-                    && j >= 0
-                    && j < self.columns as i32
-                    && let GridItem::Roll = self.mat[i as usize][j as usize]
-                {
-                    ret += 1;
-                }
+        self.neighbours8((row, col))
+            .filter(|pos| self[*pos] == GridItem::Roll)
+            .count()
+    }
+}
+
+/// Rolls with fewer rolls than this among their 8 neighbours can be reached
+pub const ACCESSIBLE_BELOW: usize = 4;
+
+/// What the parts need from a grid of rolls, so they run on [`Grid`] as well as on
+/// the bit packed [`bitgrid::BitGrid`].
+pub trait RollGrid {
+    fn rows(&self) -> usize;
+    fn cols(&self) -> usize;
+    fn is_roll(&self, pos: (usize, usize)) -> bool;
+    fn count_rolls(&self) -> usize;
+    /// Rolls with fewer than `threshold` rolls among their 8 neighbours
+    fn count_accessible(&self, threshold: usize) -> usize;
+    /// Removes at once every roll [`Self::count_accessible`] counts, returning how many
+    fn remove_accessible(&mut self, threshold: usize) -> usize;
+
+    /// Removes rolls until none is accessible, returning how many were
+    fn erode(&mut self, threshold: usize) -> usize {
+        let mut removed = 0;
+        loop {
+            match self.remove_accessible(threshold) {
+                0 => return removed,
+                n => removed += n,
             }
         }
+    }
+}
 
-        for i in [-1, 1] {
-            let i: i32 = row as i32 + i;
-            if i >= 0
-                && i < self.mat.len() as i32
-                && let GridItem::Roll = self.mat[i as usize][col]
-            {
-                ret += 1
-            }
+impl RollGrid for Grid {
+    fn rows(&self) -> usize {
+        grid::Grid::rows(self)
+    }
+
+    fn cols(&self) -> usize {
+        grid::Grid::cols(self)
+    }
+
+    fn is_roll(&self, pos: (usize, usize)) -> bool {
+        self[pos] == GridItem::Roll
+    }
+
+    fn count_rolls(&self) -> usize {
+        self.count(|item| *item == GridItem::Roll)
+    }
+
+    fn count_accessible(&self, threshold: usize) -> usize {
+        part1::solve_with(self, &Automaton::moore(threshold))
+    }
+
+    fn remove_accessible(&mut self, threshold: usize) -> usize {
+        let changes = Automaton::moore(threshold).changes(self);
+        for (pos, item) in &changes {
+            self[*pos] = *item;
         }
+        changes.len()
+    }
 
-        ret
+    /// Worklist of [`Automaton::erosion_rounds`] instead of rescanning every round
+    fn erode(&mut self, threshold: usize) -> usize {
+        part2::solve_with(self, &Automaton::moore(threshold))
     }
 }
 
+/// Rows can be indented or followed by blanks, they are trimmed before reading cells
 pub fn parse_input(input: &str) -> Grid {
-    let mat = input
-        .trim()
-        .lines()
-        .map(|line| {
-            line.trim()
-                .chars()
-                .map(|char| GridItem::from_str(&char.to_string()).unwrap())
-                .collect()
-        })
-        .collect();
-    Grid::new(mat)
+    let rows: Vec<&str> = input.trim().lines().map(str::trim).collect();
+    Grid::parse(&rows.join("\n"), GridItem::try_from).unwrap()
 }
 
 pub mod part1 {
 
     use super::*;
+    use crate::ed2025::day4::automaton::Rule;
+
+    /// Rolls `automaton` would remove in its next step
+    pub fn solve_with(input: &Grid, automaton: &Automaton<impl Rule>) -> usize {
+        automaton
+            .changes(input)
+            .iter()
+            .filter(|(pos, item)| input[*pos] == GridItem::Roll && *item == GridItem::Empty)
+            .count()
+    }
 
-    pub fn solve(input: &Grid) -> usize {
-        let mut ret = 0;
-
-        for i in 0..input.mat.len() {
-            for j in 0..input.columns {
-                let adjacent = input.n_adjacent_rolls(i, j);
-                if let GridItem::Roll = input.mat[i][j]
-                    && adjacent < 4
-                {
-                    ret += 1;
-                }
-            }
-        }
-        ret
+    pub fn solve(input: &impl RollGrid) -> usize {
+        input.count_accessible(ACCESSIBLE_BELOW)
     }
 
     #[cfg(test)]
@@ -124,8 +168,11 @@ pub mod part1 {
         #[test]
         fn test_parse() {
             let res = parse_input(EXAMPLE);
-            assert_eq!(res.columns, 10);
-            assert_eq!(res.mat.len(), 10);
+            assert_eq!(res.cols(), 10);
+            assert_eq!(res.rows(), 10);
+
+            let indented: String = EXAMPLE.lines().map(|l| format!("  {l} \n")).collect();
+            assert_eq!(parse_input(&indented), res);
         }
 
         #[test]
@@ -145,35 +192,22 @@ pub mod part1 {
 pub mod part2 {
 
     use super::*;
+    use crate::ed2025::day4::automaton::Threshold;
 
-    pub fn solve(input: &mut Grid) -> usize {
-        let mut removed = 0;
-        let mut to_remove = Vec::new();
-
-        let rows = input.mat.len();
-        let cols = input.columns;
-
-        loop {
-            for i in 0..rows {
-                for j in 0..cols {
-                    if let GridItem::Roll = input.mat[i][j]
-                        && input.n_adjacent_rolls(i, j) < 4
-                    {
-                        to_remove.push((i, j));
-                    }
-                }
-            }
-
-            if to_remove.is_empty() {
-                break;
-            }
+    /// Rolls removed by every round, each round removing at once all the rolls that
+    /// can be. `input` is left with the rolls that can't.
+    pub fn rounds(input: &mut Grid) -> Vec<Vec<(usize, usize)>> {
+        Automaton::puzzle().erosion_rounds(input)
+    }
 
-            removed += to_remove.len();
-            input.remove_rolls(&to_remove);
-            to_remove.clear();
-        }
+    /// Same as [`solve`] with other neighbourhoods, edges or threshold
+    pub fn solve_with(input: &mut Grid, automaton: &Automaton<Threshold>) -> usize {
+        automaton.erosion_rounds(input).iter().map(Vec::len).sum()
+    }
 
-        removed
+    /// Removes rolls until none can be, returning how many were.
+    pub fn solve(input: &mut impl RollGrid) -> usize {
+        input.erode(ACCESSIBLE_BELOW)
     }
 
     #[cfg(test)]
@@ -188,6 +222,13 @@ pub mod part2 {
             let mut input = parse_input(EXAMPLE);
             let res = solve(&mut input);
             assert_eq!(res, 43);
+            // Nothing left to remove
+            assert_eq!(part1::solve(&input), 0);
+            assert_eq!(input.count(|item| *item == GridItem::Roll), 71 - 43);
+
+            let mut input = parse_input(EXAMPLE);
+            let sizes: Vec<usize> = rounds(&mut input).iter().map(Vec::len).collect();
+            assert_eq!(sizes, vec![13, 12, 7, 5, 2, 1, 1, 1, 1]);
 
             let input = fs::read_to_string("./src/ed2025/day4/input.txt").unwrap();
             let mut input = parse_input(&input);
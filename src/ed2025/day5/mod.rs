@@ -10,45 +10,190 @@ pub const EXAMPLE: &str = "3-5
 17
 32";
 
-pub mod part1 {
+use std::{
+    fmt::{self, Display},
+    io,
+    str::FromStr,
+};
 
-    pub struct IdRange {
-        start: usize,
-        end: usize,
-    }
+use crate::{
+    input::{LineSource, invalid_data},
+    interval::IntervalSet,
+    parse::{self, ParseError, ParseResult},
+};
 
-    pub fn solve(input: &str) -> usize {
-        let mut fresh_ids: Vec<IdRange> = vec![];
-        let mut are_fresh = 0;
+pub mod query;
+
+/// A line of the database
+pub enum Entry {
+    /// `start-end` fresh ID range, both included
+    Range(usize, usize),
+    Id(usize),
+}
 
-        for s_line in input.lines() {
-            let line = s_line.trim();
+pub fn parse_entry(line: &str, line_number: usize) -> ParseResult<Entry> {
+    parse::line(line, line_number, |c| {
+        c.skip_spaces();
+        let first = c.uint()?;
+        if !c.eat('-') {
+            return Ok(Entry::Id(first));
+        }
+        let end = c.uint()?;
+        if end < first {
+            return Err(c.error(format!("Range ends before its start {first}")));
+        }
+        Ok(Entry::Range(first, end))
+    })
+}
 
-            if line.is_empty() {
-                continue;
+/// Part of the database a line belongs to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// Up to the first blank line
+    #[default]
+    Ranges,
+    Ids,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseError {
+    Parse(ParseError),
+    /// An ID before the blank line ending the ranges, at the given line
+    IdInRanges(usize),
+    /// A range after the blank line, at the given line
+    RangeInIds(usize),
+}
+
+impl Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::IdInRanges(line) => write!(
+                f,
+                "{line}: ID among the ranges, a blank line has to separate them"
+            ),
+            Self::RangeInIds(line) => {
+                write!(f, "{line}: Range after the blank line, among the IDs")
             }
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
 
-            let mut line = line.split("-");
-            let first: usize = line.next().unwrap().parse().unwrap();
-
-            match line.next() {
-                Some(second) => {
-                    let end = second.parse().unwrap();
-                    assert!(end >= first, "Line {s_line}");
-                    fresh_ids.push(IdRange { start: first, end });
-                }
-                None => {
-                    for IdRange { start, end } in &fresh_ids {
-                        if first >= *start && first <= *end {
-                            are_fresh += 1;
-                            break;
-                        }
-                    }
-                }
+impl From<ParseError> for DatabaseError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Reads the lines of a database in order, checking every entry is in its section.
+///
+/// Every range comes before the first ID, so IDs can be checked as they come.
+#[derive(Debug, Default)]
+pub struct SectionParser {
+    section: Section,
+    /// Some entry was read, blank lines before it are skipped
+    started: bool,
+}
+
+impl SectionParser {
+    pub fn section(&self) -> Section {
+        self.section
+    }
+
+    /// Entry of the next line, `None` for blank lines. The first blank line after the
+    /// ranges ends them, the other ones are ignored.
+    pub fn parse_line(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<Entry>, DatabaseError> {
+        if line.trim().is_empty() {
+            if self.started {
+                self.section = Section::Ids;
             }
+            return Ok(None);
+        }
+        self.started = true;
+        match (self.section, parse_entry(line, line_number)?) {
+            (Section::Ranges, Entry::Id(_)) => Err(DatabaseError::IdInRanges(line_number)),
+            (Section::Ids, Entry::Range(..)) => Err(DatabaseError::RangeInIds(line_number)),
+            (_, entry) => Ok(Some(entry)),
+        }
+    }
+}
+
+/// The whole input: fresh ID ranges as written, then the available IDs
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Database {
+    pub ranges: Vec<(usize, usize)>,
+    pub ids: Vec<usize>,
+}
+
+impl Database {
+    fn push(&mut self, entry: Entry) {
+        match entry {
+            Entry::Range(start, end) => self.ranges.push((start, end)),
+            Entry::Id(id) => self.ids.push(id),
         }
+    }
 
-        are_fresh
+    pub fn read(source: impl LineSource) -> io::Result<Self> {
+        let mut database = Self::default();
+        let mut parser = SectionParser::default();
+        source.try_for_each_line(|line_number, line| {
+            if let Some(entry) = parser.parse_line(line, line_number).map_err(invalid_data)? {
+                database.push(entry);
+            }
+            Ok(())
+        })?;
+        Ok(database)
+    }
+
+    /// Every fresh ID, overlapping ranges merged
+    pub fn fresh(&self) -> IntervalSet<usize> {
+        self.ranges.iter().copied().collect()
+    }
+}
+
+impl FromStr for Database {
+    type Err = DatabaseError;
+
+    /// Same as [`Database::read`], a `str` only fails on what it holds
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::read(s).map_err(|e| {
+            *e.into_inner()
+                .and_then(|e| e.downcast::<DatabaseError>().ok())
+                .expect("Reading a str only fails on its lines")
+        })
+    }
+}
+
+pub mod part1 {
+    use super::*;
+
+    pub fn solve(input: &str) -> usize {
+        solve_streaming(input).unwrap()
+    }
+
+    /// Only the fresh ranges are kept in memory, the IDs are checked as they come
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
+        let mut fresh_ids = IntervalSet::new();
+        let mut are_fresh = 0;
+        let mut parser = SectionParser::default();
+
+        source.try_for_each_line(|line_number, line| {
+            match parser.parse_line(line, line_number).map_err(invalid_data)? {
+                Some(Entry::Range(start, end)) => fresh_ids.insert(start, end),
+                // Every range is known by then
+                Some(Entry::Id(id)) => are_fresh += fresh_ids.contains(id) as usize,
+                None => {}
+            }
+            Ok(())
+        })?;
+
+        Ok(are_fresh)
     }
 
     #[cfg(test)]
@@ -65,50 +210,74 @@ pub mod part1 {
             let input = std::fs::read_to_string("./src/ed2025/day5/input.txt").unwrap();
             assert_eq!(solve(&input), 821);
         }
-    }
-}
-
-pub mod part2 {
-    use std::cmp::max;
 
-    pub struct IdRange {
-        start: usize,
-        end: usize,
-    }
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 3),
+                ("./src/ed2025/day5/input.txt", 821),
+            );
+            assert!(solve_streaming("5-3\n\n4").is_err());
+            assert!(solve_streaming("3-5\n4\n\n5").is_err());
+            assert!(solve_streaming("3-5\n\n4\n1-9").is_err());
+        }
 
-    pub fn solve(input: &str) -> usize {
-        let mut fresh_ids: Vec<IdRange> = vec![];
-        let mut all_fresh = 0;
+        #[test]
+        fn test_database() {
+            let database: Database = EXAMPLE.parse().unwrap();
+            assert_eq!(database.ranges.len(), 4);
+            assert_eq!(database.ids, vec![1, 5, 8, 11, 17, 32]);
+            assert_eq!(database.fresh().intervals(), &[(3, 5), (10, 20)]);
+            assert_eq!(Database::read(EXAMPLE).unwrap(), database);
 
-        for s_line in input.lines() {
-            let line = s_line.trim();
+            assert_eq!(
+                "3-5\n4\n".parse::<Database>(),
+                Err(DatabaseError::IdInRanges(2))
+            );
+            assert_eq!(
+                "3-5\n\n\n4\n6-7\n".parse::<Database>(),
+                Err(DatabaseError::RangeInIds(5))
+            );
+            assert!(matches!(
+                "3-x".parse::<Database>(),
+                Err(DatabaseError::Parse(_))
+            ));
+            assert_eq!("1-2\n".parse::<Database>().unwrap().ids, vec![]);
 
-            if line.is_empty() {
-                continue;
-            }
+            // Blank lines before the ranges don't end them
+            let padded = format!("\n \n{EXAMPLE}");
+            assert_eq!(padded.parse::<Database>(), Ok(database));
+            assert_eq!(
+                "\n3-5\n4\n".parse::<Database>(),
+                Err(DatabaseError::IdInRanges(3))
+            );
+        }
+    }
+}
 
-            let mut line = line.split("-");
-            let first: usize = line.next().unwrap().parse().unwrap();
+pub mod part2 {
+    use super::*;
 
-            if let Some(second) = line.next() {
-                let end = second.parse().unwrap();
-                assert!(end >= first, "Line {s_line}");
-                fresh_ids.push(IdRange { start: first, end });
-            }
-        }
+    pub fn solve(input: &str) -> usize {
+        solve_streaming(input).unwrap()
+    }
 
-        fresh_ids.sort_by_key(|IdRange { start, end: _ }| *start);
+    /// Only the fresh ranges are kept in memory, the IDs are skipped as they come
+    pub fn solve_streaming(source: impl LineSource) -> io::Result<usize> {
+        let mut fresh_ids = IntervalSet::new();
+        let mut parser = SectionParser::default();
 
-        let mut last_end = 0;
-        for IdRange { start, end } in fresh_ids {
-            let start = max(last_end, start);
-            if end >= start {
-                all_fresh += end - start + 1;
-                last_end = end + 1;
+        source.try_for_each_line(|line_number, line| {
+            if let Some(Entry::Range(start, end)) =
+                parser.parse_line(line, line_number).map_err(invalid_data)?
+            {
+                fresh_ids.insert(start, end);
             }
-        }
+            Ok(())
+        })?;
 
-        all_fresh
+        Ok(fresh_ids.len() as usize)
     }
 
     #[cfg(test)]
@@ -125,5 +294,15 @@ pub mod part2 {
             let input = std::fs::read_to_string("./src/ed2025/day5/input.txt").unwrap();
             assert_eq!(solve(&input), 344771884978261);
         }
+
+        #[test]
+        fn test_solve_streaming() {
+            crate::input::test::check_sources(
+                solve_streaming,
+                (EXAMPLE, 14),
+                ("./src/ed2025/day5/input.txt", 344771884978261),
+            );
+            assert!(solve_streaming("5-3\n\n4").is_err());
+        }
     }
 }
@@ -0,0 +1,166 @@
+//! Which ranges make every ID fresh, one ID at a time or a whole batch in one sweep.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::ed2025::day5::Database;
+
+/// Answer for one ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub id: usize,
+    /// Indices in [`Database::ranges`] of the ranges holding the ID, increasing
+    pub ranges: Vec<usize>,
+}
+
+impl Query {
+    pub fn is_fresh(&self) -> bool {
+        !self.ranges.is_empty()
+    }
+}
+
+/// Ranges sorted by start for answering single IDs
+#[derive(Debug, Clone)]
+pub struct RangeIndex {
+    /// Start, end and index in the original order
+    by_start: Vec<(usize, usize, usize)>,
+    /// Biggest end among the ranges up to each one, where the backward scan can stop
+    reach: Vec<usize>,
+}
+
+impl RangeIndex {
+    pub fn new(ranges: &[(usize, usize)]) -> Self {
+        let mut by_start: Vec<_> = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, (start, end))| (*start, *end, i))
+            .collect();
+        by_start.sort_unstable();
+        let reach = by_start
+            .iter()
+            .scan(0, |reach, (_, end, _)| {
+                *reach = (*reach).max(*end);
+                Some(*reach)
+            })
+            .collect();
+        Self { by_start, reach }
+    }
+
+    /// Binary search for the last range starting before `id`, then back while some
+    /// earlier range still reaches it.
+    ///
+    /// O(log n + scanned) where the scan stops at the first range that nothing before
+    /// reaches past `id`. A single wide range starting early keeps every later one in
+    /// the scan, so the worst case is O(n) per ID whatever the number of matches: use
+    /// [`query_batch`] for many IDs.
+    pub fn query(&self, id: usize) -> Query {
+        let starting_before = self.by_start.partition_point(|(start, _, _)| *start <= id);
+        let mut ranges: Vec<usize> = (0..starting_before)
+            .rev()
+            .take_while(|i| self.reach[*i] >= id)
+            .filter(|i| self.by_start[*i].1 >= id)
+            .map(|i| self.by_start[i].2)
+            .collect();
+        ranges.sort_unstable();
+        Query { id, ranges }
+    }
+}
+
+/// Answers for every ID of `ids`, in their order.
+///
+/// Ranges and IDs are both sorted and swept together, the ranges holding the current
+/// ID are kept in a heap by end: O((n + m) log n + matches) for n ranges and m IDs.
+pub fn query_batch(ranges: &[(usize, usize)], ids: &[usize]) -> Vec<Query> {
+    let mut by_start: Vec<usize> = (0..ranges.len()).collect();
+    by_start.sort_unstable_by_key(|i| ranges[*i].0);
+    let mut by_id: Vec<usize> = (0..ids.len()).collect();
+    by_id.sort_unstable_by_key(|i| ids[*i]);
+
+    let mut answers: Vec<Query> = ids
+        .iter()
+        .map(|id| Query {
+            id: *id,
+            ranges: vec![],
+        })
+        .collect();
+    let mut next_range = by_start.iter().peekable();
+    // End and index of the ranges started so far, the ones ending first on top
+    let mut open: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new();
+
+    for i in by_id {
+        let id = ids[i];
+        while let Some(r) = next_range.next_if(|r| ranges[**r].0 <= id) {
+            open.push(Reverse((ranges[*r].1, *r)));
+        }
+        while open.peek().is_some_and(|Reverse((end, _))| *end < id) {
+            open.pop();
+        }
+        let mut matching: Vec<usize> = open.iter().map(|Reverse((_, r))| *r).collect();
+        matching.sort_unstable();
+        answers[i].ranges = matching;
+    }
+    answers
+}
+
+impl Database {
+    pub fn index(&self) -> RangeIndex {
+        RangeIndex::new(&self.ranges)
+    }
+
+    /// Every ID of the database answered in one sweep, see [`query_batch`]
+    pub fn query_all(&self) -> Vec<Query> {
+        query_batch(&self.ranges, &self.ids)
+    }
+
+    /// IDs in no range, in the database order
+    pub fn spoiled(&self) -> Vec<usize> {
+        let fresh = self.fresh();
+        self.ids
+            .iter()
+            .copied()
+            .filter(|id| !fresh.contains(*id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ed2025::day5::EXAMPLE;
+
+    fn brute_force(database: &Database) -> Vec<Query> {
+        database
+            .ids
+            .iter()
+            .map(|id| Query {
+                id: *id,
+                ranges: (0..database.ranges.len())
+                    .filter(|r| (database.ranges[*r].0..=database.ranges[*r].1).contains(id))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_queries() {
+        let database: Database = EXAMPLE.parse().unwrap();
+        let answers = database.query_all();
+        // 10-14, 16-20 and 12-18
+        assert_eq!(answers[4].id, 17);
+        assert_eq!(answers[4].ranges, vec![2, 3]);
+        assert_eq!(database.spoiled(), vec![1, 8, 32]);
+
+        let input = std::fs::read_to_string("./src/ed2025/day5/input.txt").unwrap();
+        for database in [database, input.parse().unwrap()] {
+            let expected = brute_force(&database);
+            assert_eq!(database.query_all(), expected);
+            let index = database.index();
+            for query in &expected {
+                assert_eq!(index.query(query.id), *query);
+            }
+            assert_eq!(
+                database.spoiled().len(),
+                expected.iter().filter(|q| !q.is_fresh()).count()
+            );
+        }
+    }
+}
@@ -1,14 +1,25 @@
 use std::str::FromStr;
 
+use crate::ed2025::day6::worksheet::{Problem, ProblemError, Value, Worksheet};
+
+pub mod worksheet;
+
 pub const EXAMPLE: &str = "123 328  51 64 
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operation {
     Sum,
     Mul,
+    Sub,
+    /// Integer division, rounding down
+    Div,
+    Min,
+    Max,
+    /// Digits of the numbers one after the other
+    Concat,
 }
 
 impl FromStr for Operation {
@@ -18,6 +29,11 @@ impl FromStr for Operation {
         match s {
             "+" => Ok(Self::Sum),
             "*" => Ok(Self::Mul),
+            "-" => Ok(Self::Sub),
+            "/" => Ok(Self::Div),
+            "<" => Ok(Self::Min),
+            ">" => Ok(Self::Max),
+            "|" => Ok(Self::Concat),
             _ => Err(format!("String: [{s}] is not valid Operation")),
         }
     }
@@ -34,30 +50,24 @@ pub mod part1 {
             .collect()
     }
 
-    pub fn solve(input: &[Vec<String>]) -> u128 {
-        let mut sum: u128 = 0;
-        let op_row = input.len() - 1;
-
-        let n_cols = input[0].len();
-        let n_rows = input.len();
-
-        for col in 0..n_cols {
-            let op = Operation::from_str(&input[op_row][col]).unwrap();
-            let mut col_res: u128 = input[0][col].parse().unwrap();
-
-            #[allow(clippy::needless_range_loop)]
-            for row in 1..n_rows - 1 {
-                let val: u128 = input[row][col].parse().unwrap();
-                match op {
-                    Operation::Sum => col_res += val,
-                    Operation::Mul => col_res *= val,
-                }
-            }
-
-            sum += col_res
-        }
+    /// One problem per column, the operation on the last row
+    pub fn worksheet(input: &[Vec<String>]) -> Worksheet {
+        let (operations, rows) = input.split_last().unwrap();
+        Worksheet(
+            operations
+                .iter()
+                .enumerate()
+                .map(|(col, op)| Problem {
+                    numbers: rows.iter().map(|row| row[col].parse().unwrap()).collect(),
+                    operation: Operation::from_str(op).unwrap(),
+                })
+                .collect(),
+        )
+    }
 
-        sum
+    /// Grand total, see [`Worksheet::grand_total`]
+    pub fn solve(input: &[Vec<String>]) -> Result<Value, (usize, ProblemError)> {
+        worksheet(input).grand_total()
     }
 
     #[cfg(test)]
@@ -74,11 +84,24 @@ pub mod part1 {
         #[test]
         fn test_solve() {
             let input = parse_input(EXAMPLE);
-            assert_eq!(solve(&input), 4277556);
+            assert_eq!(solve(&input), Ok(Value::from(4277556)));
 
             let input =
                 parse_input(&std::fs::read_to_string("./src/ed2025/day6/input.txt").unwrap());
-            assert_eq!(solve(&input), 5784380717354);
+            assert_eq!(solve(&input), Ok(Value::from(5784380717354)));
+        }
+
+        #[test]
+        fn test_overflowing_column() {
+            let input = parse_input(&format!("{} 5\n2 9\n* -", u128::MAX));
+            let total = solve(&input);
+            assert_eq!(total, Err((1, ProblemError::Underflow)));
+
+            let input = parse_input(&format!("{} 9\n2 5\n* -", u128::MAX));
+            let total = solve(&input).unwrap();
+            assert_eq!(total.to_u128(), None);
+            // 2 * u128::MAX + 4
+            assert_eq!(total.to_string(), "680564733841876926926749214863536422914");
         }
     }
 }
@@ -130,44 +153,43 @@ pub mod part2 {
             }
         }
 
-        pub fn operate(&self) -> u128 {
+        /// Every column is a number, written top to bottom
+        pub fn problem(&self) -> Problem {
             assert!(self.is_valid());
-            let mut res = None;
             let n_cols = self.nums[0].len();
             let n_rows = self.nums.len();
-            let op = self.op.unwrap();
 
-            for col in 0..n_cols {
-                let mut col_acc: Vec<char> = vec![];
-                for row in 0..n_rows {
-                    let to_parse = self.nums[row][col];
+            let numbers = (0..n_cols)
+                .map(|col| {
+                    let mut col_acc: Vec<char> = vec![];
+                    for row in 0..n_rows {
+                        let to_parse = self.nums[row][col];
 
-                    if !to_parse.is_ascii_digit() {
-                        continue;
-                    }
+                        if !to_parse.is_ascii_digit() {
+                            continue;
+                        }
 
-                    col_acc.push(to_parse);
-                }
-
-                // INFO: I know parsing the numbers from a String is not efficient, this should
-                // be done in the parse_input function with math (i.e., multiplying by 10)
-                let num = col_acc
-                    .iter()
-                    .collect::<String>()
-                    .parse::<u128>()
-                    .expect("All cols should have a number");
-
-                if let Some(res) = &mut res {
-                    match op {
-                        Operation::Sum => *res += num,
-                        Operation::Mul => *res *= num,
+                        col_acc.push(to_parse);
                     }
-                } else {
-                    res = Some(num);
-                }
+
+                    // INFO: I know parsing the numbers from a String is not efficient, this should
+                    // be done in the parse_input function with math (i.e., multiplying by 10)
+                    col_acc
+                        .iter()
+                        .collect::<String>()
+                        .parse::<Value>()
+                        .expect("All cols should have a number")
+                })
+                .collect();
+
+            Problem {
+                numbers,
+                operation: self.op.unwrap(),
             }
+        }
 
-            res.unwrap()
+        pub fn operate(&self) -> Result<Value, ProblemError> {
+            self.problem().solve()
         }
     }
 
@@ -217,8 +239,13 @@ pub mod part2 {
         sets
     }
 
-    pub fn solve(input: &[Set]) -> u128 {
-        input.iter().map(|s| s.operate()).sum()
+    pub fn worksheet(input: &[Set]) -> Worksheet {
+        Worksheet(input.iter().map(Set::problem).collect())
+    }
+
+    /// Grand total, see [`Worksheet::grand_total`]
+    pub fn solve(input: &[Set]) -> Result<Value, (usize, ProblemError)> {
+        worksheet(input).grand_total()
     }
 
     #[cfg(test)]
@@ -235,12 +262,12 @@ pub mod part2 {
         fn test_solve() {
             let sets = parse_input(EXAMPLE);
             let res = solve(&sets);
-            assert_eq!(res, 3263827);
+            assert_eq!(res, Ok(Value::from(3263827)));
 
             let sets =
                 parse_input(&std::fs::read_to_string("./src/ed2025/day6/input.txt").unwrap());
             let res = solve(&sets);
-            assert_eq!(res, 7996218225744);
+            assert_eq!(res, Ok(Value::from(7996218225744)));
         }
     }
 }
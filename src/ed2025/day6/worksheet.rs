@@ -0,0 +1,249 @@
+//! Problems of the worksheet solved with checked arithmetic, results that overflow a
+//! `u128` go on as big integers.
+
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    str::FromStr,
+};
+
+use crate::{bigint::BigUint, ed2025::day6::Operation};
+
+/// A result, built only through `From` so it is `Big` only when it does not fit in a
+/// `u128`: equal values compare equal and every `Small` is below every `Big`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Repr {
+    Small(u128),
+    Big(BigUint),
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Self {
+        Self(Repr::Small(value))
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(value: BigUint) -> Self {
+        match value.to_u128() {
+            Some(small) => Self(Repr::Small(small)),
+            None => Self(Repr::Big(value)),
+        }
+    }
+}
+
+impl Value {
+    pub fn to_u128(&self) -> Option<u128> {
+        match &self.0 {
+            Repr::Small(value) => Some(*value),
+            Repr::Big(_) => None,
+        }
+    }
+
+    pub fn to_big(&self) -> BigUint {
+        match &self.0 {
+            Repr::Small(value) => BigUint::from(*value),
+            Repr::Big(value) => value.clone(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == Repr::Small(0)
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Repr::Small(value) => value.fmt(f),
+            Repr::Big(value) => value.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<u128>() {
+            Ok(value) => Ok(Self::from(value)),
+            Err(_) => s.parse::<BigUint>().map(Self::from),
+        }
+    }
+}
+
+impl Sum for Value {
+    fn sum<I: Iterator<Item = Value>>(iter: I) -> Self {
+        iter.fold(Value::from(0), |acc, v| {
+            Operation::Sum
+                .apply(&acc, &v)
+                .expect("Additions always succeed")
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemError {
+    /// A subtraction below 0
+    Underflow,
+    DivisionByZero,
+    NoNumbers,
+}
+
+impl Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Underflow => write!(f, "Subtraction below 0"),
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::NoNumbers => write!(f, "Problem without numbers"),
+        }
+    }
+}
+
+impl std::error::Error for ProblemError {}
+
+impl Operation {
+    /// `a` operated with `b`, in `u128` while it fits
+    pub fn apply(self, a: &Value, b: &Value) -> Result<Value, ProblemError> {
+        let small = a.to_u128().zip(b.to_u128());
+        Ok(match self {
+            Operation::Sum => match small.and_then(|(a, b)| a.checked_add(b)) {
+                Some(sum) => Value::from(sum),
+                None => Value::from(a.to_big() + &b.to_big()),
+            },
+            Operation::Mul => match small.and_then(|(a, b)| a.checked_mul(b)) {
+                Some(product) => Value::from(product),
+                None => Value::from(&a.to_big() * &b.to_big()),
+            },
+            Operation::Sub => match small {
+                Some((a, b)) => a.checked_sub(b).map(Value::from),
+                None => a.to_big().checked_sub(&b.to_big()).map(Value::from),
+            }
+            .ok_or(ProblemError::Underflow)?,
+            Operation::Div if b.is_zero() => return Err(ProblemError::DivisionByZero),
+            Operation::Div => match small {
+                Some((a, b)) => Value::from(a / b),
+                None => Value::from(a.to_big().div_rem(&b.to_big()).0),
+            },
+            Operation::Min => a.min(b).clone(),
+            Operation::Max => a.max(b).clone(),
+            Operation::Concat => format!("{a}{b}")
+                .parse()
+                .expect("Two numbers written one after the other are a number"),
+        })
+    }
+}
+
+/// Numbers of a problem, operated from left to right
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub numbers: Vec<Value>,
+    pub operation: Operation,
+}
+
+impl Problem {
+    pub fn solve(&self) -> Result<Value, ProblemError> {
+        let (first, rest) = self.numbers.split_first().ok_or(ProblemError::NoNumbers)?;
+        rest.iter()
+            .try_fold(first.clone(), |acc, n| self.operation.apply(&acc, n))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet(pub Vec<Problem>);
+
+impl Worksheet {
+    /// Result of every problem, in order
+    pub fn results(&self) -> Vec<Result<Value, ProblemError>> {
+        self.0.iter().map(Problem::solve).collect()
+    }
+
+    /// Results added up, or the index of the first problem failing and why
+    pub fn grand_total(&self) -> Result<Value, (usize, ProblemError)> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, problem)| problem.solve().map_err(|e| (i, e)))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn problem(numbers: &[u128], operation: Operation) -> Problem {
+        Problem {
+            numbers: numbers.iter().map(|n| Value::from(*n)).collect(),
+            operation,
+        }
+    }
+
+    #[test]
+    fn test_operations() {
+        let solve = |numbers: &[u128], op: &str| problem(numbers, op.parse().unwrap()).solve();
+        assert_eq!(solve(&[123, 45, 6], "*"), Ok(Value::from(33210)));
+        assert_eq!(solve(&[50, 8, 2], "-"), Ok(Value::from(40)));
+        assert_eq!(solve(&[5, 8], "-"), Err(ProblemError::Underflow));
+        assert_eq!(solve(&[100, 7, 2], "/"), Ok(Value::from(7)));
+        assert_eq!(solve(&[100, 0], "/"), Err(ProblemError::DivisionByZero));
+        assert_eq!(solve(&[12, 3, 40], "<"), Ok(Value::from(3)));
+        assert_eq!(solve(&[12, 3, 40], ">"), Ok(Value::from(40)));
+        assert_eq!(solve(&[12, 3, 40], "|"), Ok(Value::from(12340)));
+        assert_eq!(solve(&[], "+"), Err(ProblemError::NoNumbers));
+        assert!("%".parse::<Operation>().is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let max = u128::MAX;
+        let sum = problem(&[max, max], Operation::Sum).solve().unwrap();
+        assert_eq!(sum, Value::from(BigUint::from(max) + &BigUint::from(max)));
+        assert_eq!(sum.to_u128(), None);
+        assert_eq!(sum.to_string(), "680564733841876926926749214863536422910");
+
+        // Back to small once it fits
+        let back = Problem {
+            numbers: vec![sum.clone(), Value::from(max)],
+            operation: Operation::Sub,
+        };
+        assert_eq!(back.solve(), Ok(Value::from(max)));
+        let halved = Problem {
+            numbers: vec![sum, Value::from(2)],
+            operation: Operation::Div,
+        };
+        assert_eq!(halved.solve(), Ok(Value::from(max)));
+
+        let concatenated = problem(&[max, 1], Operation::Concat).solve().unwrap();
+        assert_eq!(concatenated.to_string(), format!("{max}1"));
+        assert!(concatenated > Value::from(max));
+
+        // Small numbers are the same value however they were built
+        assert_eq!(Value::from(BigUint::from(5u8)), Value::from(5));
+        let min = problem(&[7, 5], Operation::Min).solve();
+        assert_eq!(min, Ok(Value::from(BigUint::from(5u8))));
+    }
+
+    #[test]
+    fn test_worksheet() {
+        let worksheet = Worksheet(vec![
+            problem(&[2, 3], Operation::Mul),
+            problem(&[1, 2], Operation::Sub),
+            problem(&[u128::MAX, 1], Operation::Sum),
+        ]);
+        let results = worksheet.results();
+        assert_eq!(results[0], Ok(Value::from(6)));
+        assert_eq!(results[1], Err(ProblemError::Underflow));
+        assert!(results[2].as_ref().is_ok_and(|v| v.to_u128().is_none()));
+        assert_eq!(worksheet.grand_total(), Err((1, ProblemError::Underflow)));
+
+        let worksheet = Worksheet(vec![worksheet.0[0].clone(), worksheet.0[2].clone()]);
+        assert_eq!(
+            worksheet.grand_total().unwrap().to_string(),
+            (BigUint::from(u128::MAX) + &BigUint::from(7u8)).to_string()
+        );
+    }
+}
@@ -15,20 +15,50 @@ pub const EXAMPLE: &str = ".......S.......
 .^.^.^.^.^...^.
 ...............";
 
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Start,
+    Splitter,
+}
+
+impl TryFrom<char> for Tile {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Self::Empty),
+            'S' => Ok(Self::Start),
+            '^' => Ok(Self::Splitter),
+            _ => Err(format!("Invalid tile: {c}")),
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Grid<Tile> {
+    Grid::parse(input, Tile::try_from).unwrap()
+}
+
+/// Columns of the splitters found on `row`
+fn splitters(grid: &Grid<Tile>, row: usize) -> impl Iterator<Item = usize> + Clone + '_ {
+    grid.row(row)
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| **t == Tile::Splitter)
+        .map(|(i, _)| i)
+}
+
 pub mod part1 {
 
     #[allow(unused_imports)]
     use super::*;
 
     pub fn solve(input: &str) -> usize {
-        let mut lines = input.lines();
+        let grid = parse_input(input);
 
-        let first_beam_p = lines
-            .next()
-            .unwrap()
-            .chars()
-            .position(|s| s == 'S')
-            .unwrap();
+        let (start_row, first_beam_p) = grid.position(|t| *t == Tile::Start).unwrap();
 
         let mut splits = 0;
 
@@ -37,11 +67,8 @@ pub mod part1 {
 
         let mut to_push_beams: Vec<usize> = vec![];
 
-        for line in lines {
-            let splitters = line
-                .char_indices()
-                .filter(|(_, c)| *c == '^')
-                .map(|(i, _)| i);
+        for row in start_row + 1..grid.rows() {
+            let splitters = splitters(&grid, row);
 
             for bp in beam_positions.iter_mut() {
                 if splitters.clone().any(|e| e == *bp) {
@@ -88,27 +115,20 @@ pub mod part2 {
     use super::*;
 
     pub fn solve(input: &str) -> usize {
-        let mut lines = input.lines();
-
-        let first_beam_p = lines
-            .next()
-            .unwrap()
-            .chars()
-            .position(|s| s == 'S')
-            .unwrap();
+        let grid = parse_input(input);
 
-        let lines: Vec<&str> = lines.collect();
+        let (start_row, first_beam_p) = grid.position(|t| *t == Tile::Start).unwrap();
 
-        let fill_iter = std::iter::repeat_n(0usize, lines[0].len());
+        let fill_iter = std::iter::repeat_n(0usize, grid.cols());
 
         let mut timelines = Vec::from_iter(fill_iter.clone());
         timelines[first_beam_p] = 1;
 
         let mut next_row_timelines = Vec::from_iter(fill_iter);
 
-        for line in lines {
+        for row in start_row + 1..grid.rows() {
             next_row_timelines.clone_from_slice(&timelines);
-            for (idx, _) in line.char_indices().filter(|(_, c)| *c == '^') {
+            for idx in splitters(&grid, row) {
                 next_row_timelines[idx + 1] += next_row_timelines[idx];
                 next_row_timelines[idx - 1] += next_row_timelines[idx];
                 next_row_timelines[idx] = 0;
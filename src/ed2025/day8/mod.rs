@@ -20,102 +20,53 @@ const EXAMPLE: &str = "162,817,812
 984,92,344
 425,690,689";
 
-pub mod part1 {
-    use std::collections::HashMap;
-
-    #[allow(unused_imports)]
-    use super::*;
+use crate::{geometry::Vec3, graph::DisjointSet};
 
-    type Vec3 = [usize; 3];
-
-    fn euc_distance(v0: &Vec3, v1: &Vec3) -> f64 {
-        // 1. Calcular la diferencia en cada dimensión
-        let dx = v1[0] as f64 - v0[0] as f64;
-        let dy = v1[1] as f64 - v0[1] as f64;
-        let dz = v1[2] as f64 - v0[2] as f64;
-
-        // 2. Calcular el cuadrado de la diferencia y sumarlos (Distancia al cuadrado)
-        let dist_sq = dx * dx + dy * dy + dz * dz;
-
-        // 3. Aplicar la raíz cuadrada
-        (dist_sq).sqrt()
-    }
+pub fn parse_input(input: &str) -> Vec<Vec3<usize>> {
+    input.lines().map(|l| l.parse().unwrap()).collect()
+}
 
-    fn get_parent(idx: usize, map: &HashMap<usize, usize>) -> usize {
-        let mut index = idx;
+/// Every pair of junctions as (v0_idx, v1_idx, squared distance), closest first.
+/// Squared distances keep the same order as the euclidean ones without floats.
+fn sorted_pairs(junctions: &[Vec3<usize>]) -> Vec<(usize, usize, usize)> {
+    let mut distances: Vec<(usize, usize, usize)> = junctions
+        .iter()
+        .enumerate()
+        .flat_map(|(j0_idx, j0)| {
+            junctions[..j0_idx]
+                .iter()
+                .enumerate()
+                .map(move |(j1_idx, j1)| (j0_idx, j1_idx, j0.squared_euclidean(j1)))
+        })
+        .collect();
+
+    distances.sort_by_key(|(_, _, dist)| *dist);
+    distances
+}
 
-        loop {
-            let new = map.get(&index).unwrap();
-            if *new == index {
-                return index;
-            }
-            index = *new;
-        }
-    }
+pub mod part1 {
+    #[allow(unused_imports)]
+    use super::*;
 
     pub fn solve(input: &str, max_iters: usize) -> usize {
-        let junctions: Vec<[usize; 3]> = input
-            .lines()
-            .map(|l| {
-                l.split(",")
-                    .map(|s| s.parse().unwrap())
-                    .collect::<Vec<usize>>()
-                    .try_into()
-                    .unwrap()
-            })
-            .collect();
-
-        // Vec of (v0_idx, v1_idx, distance)
-        let mut distances: Vec<(usize, usize, f64)> = junctions
-            .iter()
-            .enumerate()
-            .flat_map(|(j0_idx, j0)| {
-                junctions
-                    .iter()
-                    .enumerate()
-                    .filter(move |(i, _)| *i < j0_idx)
-                    .map(move |(j1_idx, j1)| (j0_idx, j1_idx, euc_distance(j0, j1)))
-            })
-            .map(|(a, b, d)| (a.max(b), a.min(b), d))
-            .collect();
-
-        distances.sort_by_key(|(a, b, _)| (*a, *b));
-        distances.dedup();
-
-        distances.sort_by(|(_, _, dist_a), (_, _, dist_b)| dist_a.partial_cmp(dist_b).unwrap());
-
-        // key: idx of a junction
-        // value: idx of the junction that represents the circuit (defaults to itself)
-        let mut map: HashMap<usize, usize> =
-            HashMap::from_iter(junctions.iter().enumerate().map(|(idx, _)| (idx, idx)));
-
-        let mut distances = distances.iter();
-
-        for _ in 0..max_iters {
-            if let Some((v0_idx, v1_idx, _)) = distances.next() {
-                let v0_p = get_parent(*v0_idx, &map);
-                let v1_p = get_parent(*v1_idx, &map);
-
-                // They are from different circuit
-                if v0_p != v1_p {
-                    map.insert(v0_p, v1_p).unwrap();
-                }
-            }
-        }
+        let junctions = parse_input(input);
+        let distances = sorted_pairs(&junctions);
 
-        let mut circuits = Vec::from_iter(std::iter::repeat_n(0usize, junctions.len()));
+        // Every set is a circuit
+        let mut circuits = DisjointSet::new(junctions.len());
 
-        for k in map.keys() {
-            circuits[get_parent(*k, &map)] += 1;
+        for (v0_idx, v1_idx, _) in distances.iter().take(max_iters) {
+            circuits.union(*v0_idx, *v1_idx);
         }
 
-        circuits.sort();
+        let mut sizes = circuits.sizes();
+
+        sizes.sort();
 
-        circuits
+        sizes
             .into_iter()
             .rev()
             .take(3)
-            .map(|e| dbg!(e))
             .reduce(|acc, b| acc * b)
             .unwrap()
     }
@@ -138,95 +89,24 @@ pub mod part1 {
 }
 
 pub mod part2 {
-    use std::collections::HashMap;
-
     #[allow(unused_imports)]
     use super::*;
 
-    type Vec3 = [usize; 3];
-
-    fn euc_distance(v0: &Vec3, v1: &Vec3) -> f64 {
-        // 1. Calcular la diferencia en cada dimensión
-        let dx = v1[0] as f64 - v0[0] as f64;
-        let dy = v1[1] as f64 - v0[1] as f64;
-        let dz = v1[2] as f64 - v0[2] as f64;
-
-        // 2. Calcular el cuadrado de la diferencia y sumarlos (Distancia al cuadrado)
-        let dist_sq = dx * dx + dy * dy + dz * dz;
-
-        // 3. Aplicar la raíz cuadrada
-        (dist_sq).sqrt()
-    }
+    pub fn solve(input: &str) -> usize {
+        let junctions = parse_input(input);
+        let distances = sorted_pairs(&junctions);
 
-    fn get_parent(idx: usize, map: &HashMap<usize, usize>) -> usize {
-        let mut index = idx;
+        // Every set is a circuit
+        let mut circuits = DisjointSet::new(junctions.len());
 
-        loop {
-            let new = map.get(&index).unwrap();
-            if *new == index {
-                return index;
+        for (v0_idx, v1_idx, _) in distances.iter() {
+            // They are from different circuit
+            if circuits.union(*v0_idx, *v1_idx) && circuits.count() == 1 {
+                return junctions[*v0_idx].x * junctions[*v1_idx].x;
             }
-            index = *new;
         }
-    }
 
-    pub fn solve(input: &str) -> usize {
-        let junctions: Vec<[usize; 3]> = input
-            .lines()
-            .map(|l| {
-                l.split(",")
-                    .map(|s| s.parse().unwrap())
-                    .collect::<Vec<usize>>()
-                    .try_into()
-                    .unwrap()
-            })
-            .collect();
-
-        // Vec of (v0_idx, v1_idx, distance)
-        let mut distances: Vec<(usize, usize, f64)> = junctions
-            .iter()
-            .enumerate()
-            .flat_map(|(j0_idx, j0)| {
-                junctions
-                    .iter()
-                    .enumerate()
-                    .filter(move |(i, _)| *i < j0_idx)
-                    .map(move |(j1_idx, j1)| (j0_idx, j1_idx, euc_distance(j0, j1)))
-            })
-            .map(|(a, b, d)| (a.max(b), a.min(b), d))
-            .collect();
-
-        distances.sort_by_key(|(a, b, _)| (*a, *b));
-        distances.dedup();
-
-        distances.sort_by(|(_, _, dist_a), (_, _, dist_b)| dist_a.partial_cmp(dist_b).unwrap());
-
-        // key: idx of a junction
-        // value: idx of the junction that represents the circuit (defaults to itself)
-        let mut map: HashMap<usize, usize> =
-            HashMap::from_iter(junctions.iter().enumerate().map(|(idx, _)| (idx, idx)));
-
-        let mut distances = distances.iter();
-
-        let mut count = junctions.len();
-
-        loop {
-            if let Some((v0_idx, v1_idx, _)) = distances.next() {
-                let v0_p = get_parent(*v0_idx, &map);
-                let v1_p = get_parent(*v1_idx, &map);
-
-                // They are from different circuit
-                if v0_p != v1_p {
-                    map.insert(v0_p, v1_p).unwrap();
-                    count -= 1;
-                    if count == 1 {
-                        return junctions[*v0_idx][0] * junctions[*v1_idx][0];
-                    }
-                }
-            } else {
-                panic!("Run out of distances")
-            }
-        }
+        panic!("Run out of distances")
     }
 
     #[cfg(test)]
@@ -8,32 +8,23 @@ const EXAMPLE: &str = "7,1
 2,3
 7,3";
 
-#[derive(Default, Debug, Clone, Copy)]
-pub struct Vec2(isize, isize);
+use crate::geometry::{self, Rect};
 
-impl Vec2 {
-    pub fn add(&mut self, b: &Vec2) {
-        self.0 += b.0;
-        self.1 += b.1;
-    }
+pub type Vec2 = geometry::Vec2<isize>;
 
-    pub fn some_neg(&self) -> bool {
-        self.0 < 0 || self.1 < 0
-    }
+pub fn area(v0: &Vec2, v1: &Vec2) -> usize {
+    Rect::from_corners(*v0, *v1).area()
 }
 
-pub fn area(v0: &Vec2, v1: &Vec2) -> usize {
-    (v0.0.abs_diff(v1.0) + 1) * (v0.1.abs_diff(v1.1) + 1)
+pub fn parse_input(input: &str) -> Vec<Vec2> {
+    input.lines().map(|l| l.parse().unwrap()).collect()
 }
 pub mod part1 {
 
     use super::*;
 
     pub fn solve(input: &str) -> usize {
-        let parsed = input.lines().map(|l| {
-            let mut coords = l.split(",").map(|c| c.parse::<isize>().unwrap());
-            Vec2(coords.next().unwrap(), coords.next().unwrap())
-        });
+        let parsed = parse_input(input).into_iter();
 
         let (_, area) = parsed.clone().enumerate().skip(1).fold(
             ((Vec2::default(), Vec2::default()), 0usize),
@@ -76,23 +67,13 @@ pub mod part1 {
 pub mod part2 {
     use super::*;
 
-    fn parse_input(input: &str) -> Vec<Vec2> {
-        input
-            .lines()
-            .map(|l| {
-                let mut coords = l.split(",").map(|c| c.parse::<isize>().unwrap());
-                Vec2(coords.next().unwrap(), coords.next().unwrap())
-            })
-            .collect()
-    }
-
     // Check if point p is on the segment defined by a and b.
     fn is_on_segment(p: &Vec2, a: &Vec2, b: &Vec2) -> bool {
         // Must be collinear and within the bounding box of the segment.
         // This is like checking if the slops of the lines p-a b-a are the same
-        (b.1 - a.1) * (p.0 - a.0) == (p.1 - a.1) * (b.0 - a.0) // Collinear check (cross-product is 0)
-            && p.0 >= isize::min(a.0, b.0) && p.0 <= isize::max(a.0, b.0)
-            && p.1 >= isize::min(a.1, b.1) && p.1 <= isize::max(a.1, b.1)
+        (b.y - a.y) * (p.x - a.x) == (p.y - a.y) * (b.x - a.x) // Collinear check (cross-product is 0)
+            && p.x >= isize::min(a.x, b.x) && p.x <= isize::max(a.x, b.x)
+            && p.y >= isize::min(a.y, b.y) && p.y <= isize::max(a.y, b.y)
     }
 
     // Ray Casting Algorithm to check if a point is inside a polygon.
@@ -112,23 +93,23 @@ pub mod part2 {
             }
 
             // 2. Ray Casting (Winding Number variant for simplicity)
-            // We use the horizontal ray (y = p.1) for simplicity in the ray casting/crossing count,
+            // We use the horizontal ray (y = p.y) for simplicity in the ray casting/crossing count,
             // but the problem geometry (axis-aligned segments) allows for a simple crossing count.
 
             // Check if the segment crosses the horizontal ray extending from p to the right
-            if p1.1 <= p.1 {
-                if p2.1 > p.1 {
+            if p1.y <= p.y {
+                if p2.y > p.y {
                     // Upward crossing
-                    let cross = (p2.0 - p1.0) * (p.1 - p1.1) - (p2.1 - p1.1) * (p.0 - p1.0);
+                    let cross = (p2.x - p1.x) * (p.y - p1.y) - (p2.y - p1.y) * (p.x - p1.x);
                     if cross > 0 {
                         winding_number += 1;
                     }
                 }
             } else {
-                // p1.1 > p.1
-                if p2.1 <= p.1 {
+                // p1.y > p.y
+                if p2.y <= p.y {
                     // Downward crossing
-                    let cross = (p2.0 - p1.0) * (p.1 - p1.1) - (p2.1 - p1.1) * (p.0 - p1.0);
+                    let cross = (p2.x - p1.x) * (p.y - p1.y) - (p2.y - p1.y) * (p.x - p1.x);
                     if cross < 0 {
                         winding_number -= 1;
                     }
@@ -146,10 +127,9 @@ pub mod part2 {
     }
 
     fn boundary_intersects_rectangle(r1: &Vec2, r2: &Vec2, red_tiles: &[Vec2]) -> bool {
-        let rx_min = isize::min(r1.0, r2.0);
-        let rx_max = isize::max(r1.0, r2.0);
-        let ry_min = isize::min(r1.1, r2.1);
-        let ry_max = isize::max(r1.1, r2.1);
+        let rect = Rect::from_corners(*r1, *r2);
+        let (rx_min, rx_max) = (rect.min.x, rect.max.x);
+        let (ry_min, ry_max) = (rect.min.y, rect.max.y);
 
         let n = red_tiles.len();
         for i in 0..n {
@@ -157,14 +137,13 @@ pub mod part2 {
             let p2 = &red_tiles[(i + 1) % n];
 
             // Determine segment bounds
-            let seg_x_min = isize::min(p1.0, p2.0);
-            let seg_x_max = isize::max(p1.0, p2.0);
-            let seg_y_min = isize::min(p1.1, p2.1);
-            let seg_y_max = isize::max(p1.1, p2.1);
+            let seg = Rect::from_corners(*p1, *p2);
+            let (seg_x_min, seg_x_max) = (seg.min.x, seg.max.x);
+            let (seg_y_min, seg_y_max) = (seg.min.y, seg.max.y);
 
             // Check for Vertical Segment (x is constant) intersecting the rectangle
-            if p1.0 == p2.0 {
-                let seg_x = p1.0;
+            if p1.x == p2.x {
+                let seg_x = p1.x;
                 // A vertical wall intersects if its X is strictly between the rect's Left/Right
                 // AND its Y range overlaps with the rect's Y range.
                 if seg_x > rx_min && seg_x < rx_max {
@@ -175,8 +154,8 @@ pub mod part2 {
                 }
             }
             // Check for Horizontal Segment (y is constant) intersecting the rectangle
-            else if p1.1 == p2.1 {
-                let seg_y = p1.1;
+            else if p1.y == p2.y {
+                let seg_y = p1.y;
                 // A horizontal wall intersects if its Y is strictly between the rect's Top/Bottom
                 // AND its X range overlaps with the rect's X range.
                 if seg_y > ry_min
@@ -209,8 +188,8 @@ pub mod part2 {
                 // Determine the two implicit (non-red) corners of the rectangle:
                 // C1 = (R1.x, R2.y)
                 // C2 = (R2.x, R1.y)
-                let c1 = Vec2(r1.0, r2.1);
-                let c2 = Vec2(r2.0, r1.1);
+                let c1 = Vec2::new(r1.x, r2.y);
+                let c2 = Vec2::new(r2.x, r1.y);
 
                 // Check if C1 and C2 are inside or on the boundary of the red/green polygon.
                 // and no other boundary intersects the rectangle
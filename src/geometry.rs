@@ -0,0 +1,346 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::parse::Cursor;
+
+/// Integer types usable as point coordinates.
+pub trait Coord:
+    Copy
+    + Ord
+    + Default
+    + Debug
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    /// Type of the distances between two coordinates
+    type Distance: Copy
+        + Ord
+        + Default
+        + Debug
+        + Add<Output = Self::Distance>
+        + Mul<Output = Self::Distance>;
+
+    /// Distance between two adjacent cells
+    const UNIT: Self::Distance;
+
+    fn abs_diff(self, other: Self) -> Self::Distance;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty => $d:ty),*) => {
+        $(
+            impl Coord for $t {
+                type Distance = $d;
+
+                const UNIT: $d = 1;
+
+                fn abs_diff(self, other: Self) -> $d {
+                    <$t>::abs_diff(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize
+);
+
+fn parse_coords<T: Coord, const N: usize>(s: &str) -> Result<[T; N], String> {
+    let mut cursor = Cursor::new(s);
+    let coords = cursor.sep_by(',', |c| {
+        c.skip_spaces();
+        let coord = c.int()?;
+        c.skip_spaces();
+        Ok(coord)
+    })?;
+    cursor.finish()?;
+    coords
+        .try_into()
+        .map_err(|v: Vec<T>| format!("Expected {N} coordinates, found {} in [{s}]", v.len()))
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T::Distance {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T::Distance {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    pub fn squared_euclidean(&self, other: &Self) -> T::Distance {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        dx * dx + dy * dy
+    }
+
+    /// Component-wise minimum
+    pub fn component_min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// Component-wise maximum
+    pub fn component_max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+impl<T: Coord> FromStr for Vec2<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_coords(s)?;
+        Ok(Self { x, y })
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T::Distance {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T::Distance {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    pub fn squared_euclidean(&self, other: &Self) -> T::Distance {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        let dz = self.z.abs_diff(other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Component-wise minimum
+    pub fn component_min(self, other: Self) -> Self {
+        Self::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum
+    pub fn component_max(self, other: Self) -> Self {
+        Self::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+}
+
+impl<T: Coord> FromStr for Vec3<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_coords(s)?;
+        Ok(Self { x, y, z })
+    }
+}
+
+macro_rules! impl_ops {
+    ($name:ident { $($field:ident),* }) => {
+        impl<T: Coord> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Coord + Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+    };
+}
+
+impl_ops!(Vec2 { x, y });
+impl_ops!(Vec3 { x, y, z });
+
+/// Component-wise `(min, max)` corners of all the points, `None` if there are none.
+pub fn bounding_box3<T: Coord>(
+    points: impl IntoIterator<Item = Vec3<T>>,
+) -> Option<(Vec3<T>, Vec3<T>)> {
+    points.into_iter().fold(None, |acc, p| match acc {
+        None => Some((p, p)),
+        Some((min, max)) => Some((min.component_min(p), max.component_max(p))),
+    })
+}
+
+/// Axis-aligned rectangle, both corners included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T> {
+    pub min: Vec2<T>,
+    pub max: Vec2<T>,
+}
+
+impl<T: Coord> Rect<T> {
+    /// Rectangle with `a` and `b` as opposite corners, in any order.
+    pub fn from_corners(a: Vec2<T>, b: Vec2<T>) -> Self {
+        Self {
+            min: a.component_min(b),
+            max: a.component_max(b),
+        }
+    }
+
+    /// Smallest rectangle holding all the points, `None` if there are none.
+    pub fn bounding(points: impl IntoIterator<Item = Vec2<T>>) -> Option<Self> {
+        points.into_iter().fold(None, |acc, p| match acc {
+            None => Some(Self { min: p, max: p }),
+            Some(r) => Some(Self {
+                min: r.min.component_min(p),
+                max: r.max.component_max(p),
+            }),
+        })
+    }
+
+    /// Number of cells covered along x
+    pub fn width(&self) -> T::Distance {
+        self.max.x.abs_diff(self.min.x) + T::UNIT
+    }
+
+    /// Number of cells covered along y
+    pub fn height(&self) -> T::Distance {
+        self.max.y.abs_diff(self.min.y) + T::UNIT
+    }
+
+    /// Number of cells covered
+    pub fn area(&self) -> T::Distance {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, p: &Vec2<T>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Self {
+            min: self.min.component_max(other.min),
+            max: self.max.component_min(other.max),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("7,-1".parse::<Vec2<isize>>(), Ok(Vec2::new(7, -1)));
+        assert_eq!(
+            "162, 817,812".parse::<Vec3<usize>>(),
+            Ok(Vec3::new(162, 817, 812))
+        );
+        assert!("1,2,3".parse::<Vec2<isize>>().is_err());
+        assert!("1,a".parse::<Vec2<isize>>().is_err());
+    }
+
+    #[test]
+    fn test_ops_and_distances() {
+        let a = Vec2::new(1isize, -2);
+        let b = Vec2::new(-3isize, 4);
+        assert_eq!(a + b, Vec2::new(-2, 2));
+        assert_eq!(a - b, Vec2::new(4, -6));
+        assert_eq!(-a * 2, Vec2::new(-2, 4));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!(a.squared_euclidean(&b), 52);
+
+        let a = Vec3::new(162usize, 817, 812);
+        let b = Vec3::new(425usize, 690, 689);
+        assert_eq!(a.squared_euclidean(&b), 263 * 263 + 127 * 127 + 123 * 123);
+    }
+
+    #[test]
+    fn test_rect() {
+        let r = Rect::from_corners(Vec2::new(11isize, 1), Vec2::new(2, 5));
+        assert_eq!(r.area(), 50);
+        assert!(r.contains(&Vec2::new(2, 1)));
+        assert!(!r.contains(&Vec2::new(1, 1)));
+
+        let other = Rect::from_corners(Vec2::new(10, 5), Vec2::new(20, 20));
+        assert_eq!(
+            r.intersection(&other),
+            Some(Rect::from_corners(Vec2::new(10, 5), Vec2::new(11, 5)))
+        );
+
+        let bb = Rect::bounding([Vec2::new(3, 4), Vec2::new(-1, 7), Vec2::new(2, 0)]).unwrap();
+        assert_eq!(bb.min, Vec2::new(-1, 0));
+        assert_eq!(bb.max, Vec2::new(3, 7));
+        assert_eq!(
+            bounding_box3([Vec3::new(1u8, 5, 2), Vec3::new(4, 0, 3)]),
+            Some((Vec3::new(1, 0, 2), Vec3::new(4, 5, 3)))
+        );
+    }
+}
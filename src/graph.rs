@@ -0,0 +1,605 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use crate::parse::{self, ParseResult};
+
+pub type NodeId = usize;
+
+/// Maps node names to dense ids, in insertion order.
+#[derive(Default, Debug, Clone)]
+pub struct Interner {
+    ids: HashMap<String, NodeId>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    pub fn get(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Read access to the successors of every node, shared by the algorithms below.
+pub trait Adjacency {
+    fn node_count(&self) -> usize;
+
+    fn neighbours(&self, node: NodeId) -> &[NodeId];
+}
+
+/// Directed graph stored as adjacency lists, with named nodes.
+#[derive(Default, Debug, Clone)]
+pub struct Graph {
+    names: Interner,
+    adj: Vec<Vec<NodeId>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses lines like `aaa: bbb ccc`, each one adding the edges `aaa -> bbb` and `aaa -> ccc`.
+    pub fn parse_adjacency(input: &str) -> ParseResult<Self> {
+        let mut graph = Self::new();
+        for (i, line) in input.lines().enumerate() {
+            if !line.trim().is_empty() {
+                graph.add_adjacency_line(line, i + 1)?;
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Adds the edges described by a single `aaa: bbb ccc` line.
+    pub fn add_adjacency_line(&mut self, line: &str, line_number: usize) -> ParseResult<()> {
+        let (from, to) = parse::line(line, line_number, |c| {
+            c.skip_spaces();
+            let from = c.take_while(|ch| ch != ':' && !ch.is_whitespace());
+            if from.is_empty() {
+                return Err(c.error("Missing source node"));
+            }
+            c.skip_spaces();
+            c.expect(':')?;
+
+            let mut to = vec![];
+            c.take_while(char::is_whitespace);
+            while !c.is_eof() {
+                to.push(c.word()?);
+                c.take_while(char::is_whitespace);
+            }
+            Ok((from, to))
+        })?;
+
+        let from = self.add_node(from);
+        for to in to {
+            let to = self.add_node(to);
+            self.add_edge(from, to);
+        }
+        Ok(())
+    }
+
+    pub fn add_node(&mut self, name: &str) -> NodeId {
+        let id = self.names.intern(name);
+        if id == self.adj.len() {
+            self.adj.push(Vec::new());
+        }
+        id
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.adj[from].push(to);
+    }
+
+    pub fn node(&self, name: &str) -> Option<NodeId> {
+        self.names.get(name)
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        self.names.name(id)
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(Vec::len).sum()
+    }
+
+    pub fn to_csr(&self) -> Csr {
+        Csr::from_adjacency(self)
+    }
+}
+
+impl Adjacency for Graph {
+    fn node_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    fn neighbours(&self, node: NodeId) -> &[NodeId] {
+        &self.adj[node]
+    }
+}
+
+/// Compressed sparse row form: every successor list packed in a single vec.
+#[derive(Default, Debug, Clone)]
+pub struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<NodeId>,
+}
+
+impl Csr {
+    pub fn from_adjacency(graph: &impl Adjacency) -> Self {
+        let mut offsets = Vec::with_capacity(graph.node_count() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for node in 0..graph.node_count() {
+            targets.extend_from_slice(graph.neighbours(node));
+            offsets.push(targets.len());
+        }
+        Self { offsets, targets }
+    }
+
+    pub fn from_edges(node_count: usize, edges: &[(NodeId, NodeId)]) -> Self {
+        let mut offsets = vec![0; node_count + 1];
+        for (from, _) in edges {
+            offsets[from + 1] += 1;
+        }
+        for i in 0..node_count {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut next = offsets.clone();
+        let mut targets = vec![0; edges.len()];
+        for (from, to) in edges {
+            targets[next[*from]] = *to;
+            next[*from] += 1;
+        }
+        Self { offsets, targets }
+    }
+}
+
+impl Adjacency for Csr {
+    fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn neighbours(&self, node: NodeId) -> &[NodeId] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+/// Nodes reachable from `start`, in breadth first order.
+pub fn bfs(graph: &impl Adjacency, start: NodeId) -> Vec<NodeId> {
+    let mut seen = vec![false; graph.node_count()];
+    let mut order = vec![start];
+    seen[start] = true;
+    let mut i = 0;
+    while let Some(&node) = order.get(i) {
+        for &next in graph.neighbours(node) {
+            if !seen[next] {
+                seen[next] = true;
+                order.push(next);
+            }
+        }
+        i += 1;
+    }
+    order
+}
+
+/// Nodes reachable from `start`, in depth first preorder.
+pub fn dfs(graph: &impl Adjacency, start: NodeId) -> Vec<NodeId> {
+    let mut seen = vec![false; graph.node_count()];
+    let mut order = vec![];
+    let mut to_visit = vec![start];
+    while let Some(node) = to_visit.pop() {
+        if seen[node] {
+            continue;
+        }
+        seen[node] = true;
+        order.push(node);
+        // Reversed so the first neighbour is the first one visited
+        to_visit.extend(graph.neighbours(node).iter().rev().filter(|n| !seen[**n]));
+    }
+    order
+}
+
+/// Number of edges of the shortest path from `start` to every node.
+pub fn distances(graph: &impl Adjacency, start: NodeId) -> Vec<Option<usize>> {
+    let mut dist = vec![None; graph.node_count()];
+    dist[start] = Some(0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let d = dist[node].unwrap();
+        for &next in graph.neighbours(node) {
+            if dist[next].is_none() {
+                dist[next] = Some(d + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    dist
+}
+
+/// One of the paths from `from` to `to` with the fewest edges, both ends included.
+pub fn shortest_path(graph: &impl Adjacency, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+    let mut parent = vec![None; graph.node_count()];
+    parent[from] = Some(from);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = vec![to];
+            let mut node = to;
+            while node != from {
+                node = parent[node].unwrap();
+                path.push(node);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in graph.neighbours(node) {
+            if parent[next].is_none() {
+                parent[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Lowest total weight from `start` to every node, `weight` gives the cost of each edge.
+pub fn dijkstra(
+    graph: &impl Adjacency,
+    start: NodeId,
+    weight: impl Fn(NodeId, NodeId) -> u64,
+) -> Vec<Option<u64>> {
+    let mut dist = vec![None; graph.node_count()];
+    let mut heap = BinaryHeap::from([Reverse((0u64, start))]);
+    while let Some(Reverse((d, node))) = heap.pop() {
+        if dist[node].is_some() {
+            continue;
+        }
+        dist[node] = Some(d);
+        for &next in graph.neighbours(node) {
+            if dist[next].is_none() {
+                heap.push(Reverse((d + weight(node, next), next)));
+            }
+        }
+    }
+    dist
+}
+
+/// Kahn's algorithm, `None` if the graph has a cycle.
+pub fn topological_sort(graph: &impl Adjacency) -> Option<Vec<NodeId>> {
+    let n = graph.node_count();
+    let mut in_degree = vec![0usize; n];
+    for node in 0..n {
+        for &next in graph.neighbours(node) {
+            in_degree[next] += 1;
+        }
+    }
+
+    let mut order: Vec<NodeId> = (0..n).filter(|node| in_degree[*node] == 0).collect();
+    let mut i = 0;
+    while let Some(&node) = order.get(i) {
+        for &next in graph.neighbours(node) {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                order.push(next);
+            }
+        }
+        i += 1;
+    }
+
+    (order.len() == n).then_some(order)
+}
+
+/// Some cycle of the graph as the list of its nodes, `None` if the graph is acyclic.
+pub fn find_cycle(graph: &impl Adjacency) -> Option<Vec<NodeId>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        InStack,
+        Done,
+    }
+
+    let n = graph.node_count();
+    let mut state = vec![State::New; n];
+
+    for root in 0..n {
+        if state[root] != State::New {
+            continue;
+        }
+        // (node, index of the next neighbour to look at)
+        let mut stack = vec![(root, 0)];
+        state[root] = State::InStack;
+
+        while let Some((node, next_i)) = stack.last_mut() {
+            let node = *node;
+            match graph.neighbours(node).get(*next_i) {
+                Some(&next) => {
+                    *next_i += 1;
+                    match state[next] {
+                        State::New => {
+                            state[next] = State::InStack;
+                            stack.push((next, 0));
+                        }
+                        State::InStack => {
+                            let start = stack.iter().position(|(n, _)| *n == next).unwrap();
+                            return Some(stack[start..].iter().map(|(n, _)| *n).collect());
+                        }
+                        State::Done => {}
+                    }
+                }
+                None => {
+                    state[node] = State::Done;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    None
+}
+
+pub fn has_cycle(graph: &impl Adjacency) -> bool {
+    find_cycle(graph).is_some()
+}
+
+/// Nodes with a path to `to`, `to` included.
+pub fn reaching(graph: &impl Adjacency, to: NodeId) -> Vec<bool> {
+    let edges: Vec<(NodeId, NodeId)> = (0..graph.node_count())
+        .flat_map(|node| graph.neighbours(node).iter().map(move |next| (*next, node)))
+        .collect();
+    let reversed = Csr::from_edges(graph.node_count(), &edges);
+    let mut reaching = vec![false; graph.node_count()];
+    for node in bfs(&reversed, to) {
+        reaching[node] = true;
+    }
+    reaching
+}
+
+/// Paths to `to` from the nodes in `within`, counted in topological order of the edges
+/// between them. `None` if they have a cycle.
+fn count_paths_within(graph: &impl Adjacency, to: NodeId, within: &[bool]) -> Option<Vec<usize>> {
+    let inner = |node: &&NodeId| within[**node];
+    let mut in_degree = vec![0usize; graph.node_count()];
+    for node in (0..graph.node_count()).filter(|n| within[*n]) {
+        for &next in graph.neighbours(node).iter().filter(inner) {
+            in_degree[next] += 1;
+        }
+    }
+
+    let mut order: Vec<NodeId> = (0..graph.node_count())
+        .filter(|node| within[*node] && in_degree[*node] == 0)
+        .collect();
+    let mut i = 0;
+    while let Some(&node) = order.get(i) {
+        for &next in graph.neighbours(node).iter().filter(inner) {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                order.push(next);
+            }
+        }
+        i += 1;
+    }
+    if order.len() != within.iter().filter(|w| **w).count() {
+        return None;
+    }
+
+    let mut paths = vec![0; graph.node_count()];
+    paths[to] = 1;
+    for &node in order.iter().rev() {
+        if node != to {
+            paths[node] = graph.neighbours(node).iter().map(|n| paths[*n]).sum();
+        }
+    }
+    Some(paths)
+}
+
+/// Number of distinct paths from every node to `to`. `None` if a cycle can reach `to`,
+/// as the count would be infinite, cycles elsewhere don't matter.
+pub fn count_paths_to(graph: &impl Adjacency, to: NodeId) -> Option<Vec<usize>> {
+    count_paths_within(graph, to, &reaching(graph, to))
+}
+
+/// Number of distinct paths from `from` to `to`. Only the nodes on some of those paths
+/// are looked at, so `None` only if one of the paths can go around a cycle.
+pub fn count_paths(graph: &impl Adjacency, from: NodeId, to: NodeId) -> Option<usize> {
+    let mut within = reaching(graph, to);
+    let mut reachable = vec![false; graph.node_count()];
+    for node in bfs(graph, from) {
+        reachable[node] = true;
+    }
+    for (node, within) in within.iter_mut().enumerate() {
+        *within &= reachable[node];
+    }
+    count_paths_within(graph, to, &within).map(|paths| paths[from])
+}
+
+/// Groups of nodes connected when ignoring edge directions.
+pub fn connected_components(graph: &impl Adjacency) -> Vec<Vec<NodeId>> {
+    let n = graph.node_count();
+    let mut sets = DisjointSet::new(n);
+    for node in 0..n {
+        for &next in graph.neighbours(node) {
+            sets.union(node, next);
+        }
+    }
+
+    let mut components: HashMap<usize, Vec<NodeId>> = HashMap::new();
+    for node in 0..n {
+        components.entry(sets.find(node)).or_default().push(node);
+    }
+    let mut components: Vec<Vec<NodeId>> = components.into_values().collect();
+    components.sort();
+    components
+}
+
+/// Union-find over `0..n` with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Joins the sets of `a` and `b`, `false` if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    /// Size of the set holding `x`
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Size of every set, in no particular order.
+    pub fn sizes(&self) -> Vec<usize> {
+        (0..self.parent.len())
+            .filter(|x| self.parent[*x] == *x)
+            .map(|root| self.size[root])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "a: b c
+b: d
+c: d e
+d: f
+e: f";
+
+    #[test]
+    fn test_parse_adjacency() {
+        let graph = Graph::parse_adjacency(EXAMPLE).unwrap();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 7);
+        let c = graph.node("c").unwrap();
+        let names: Vec<&str> = graph.neighbours(c).iter().map(|n| graph.name(*n)).collect();
+        assert_eq!(names, ["d", "e"]);
+        assert!(Graph::parse_adjacency("a b").is_err());
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph = Graph::parse_adjacency(EXAMPLE).unwrap();
+        let csr = graph.to_csr();
+        let name = |ids: Vec<NodeId>| ids.into_iter().map(|n| graph.name(n)).collect::<String>();
+
+        assert_eq!(name(bfs(&graph, 0)), "abcdef");
+        assert_eq!(name(dfs(&csr, 0)), "abdfce");
+        assert_eq!(distances(&graph, 0)[graph.node("f").unwrap()], Some(3));
+        assert_eq!(name(shortest_path(&csr, 0, 5).unwrap()), "abdf");
+        assert_eq!(shortest_path(&csr, 5, 0), None);
+        assert_eq!(
+            dijkstra(&graph, 0, |a, b| (a + b) as u64)[5],
+            Some(1 + 4 + 8)
+        );
+    }
+
+    #[test]
+    fn test_dag() {
+        let graph = Graph::parse_adjacency(EXAMPLE).unwrap();
+        let order = topological_sort(&graph).unwrap();
+        assert_eq!(order.first(), Some(&0));
+        assert!(!has_cycle(&graph));
+        assert_eq!(count_paths(&graph, 0, 5), Some(3));
+
+        let cyclic = Graph::parse_adjacency("a: b\nb: c\nc: a d").unwrap();
+        assert_eq!(topological_sort(&cyclic), None);
+        assert_eq!(find_cycle(&cyclic), Some(vec![0, 1, 2]));
+        assert_eq!(count_paths(&cyclic, 0, 3), None);
+
+        // Cycles off every path between the two ends don't matter
+        let graph = Graph::parse_adjacency("a: b w\nb: c\nw: w\nx: y\ny: x b").unwrap();
+        let [a, c, x] = ["a", "c", "x"].map(|n| graph.node(n).unwrap());
+        assert_eq!(count_paths(&graph, a, c), Some(1));
+        assert_eq!(count_paths(&graph, x, c), None);
+        assert_eq!(count_paths_to(&graph, c), None);
+        assert_eq!(
+            reaching(&graph, 1),
+            vec![true, true, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let csr = Csr::from_edges(6, &[(0, 1), (2, 1), (3, 4)]);
+        assert_eq!(
+            connected_components(&csr),
+            vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+        );
+
+        let mut sets = DisjointSet::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 1));
+        assert!(!sets.union(0, 3));
+        assert_eq!(sets.size(3), 3);
+        assert_eq!(sets.count(), 3);
+        let mut sizes = sets.sizes();
+        sizes.sort();
+        assert_eq!(sizes, [1, 1, 3]);
+    }
+}
@@ -0,0 +1,300 @@
+use std::ops::{Index, IndexMut};
+
+use crate::parse::{self, ParseResult};
+
+/// Offsets of the 4 orthogonal neighbours, as (row, col) deltas.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of the 8 surrounding neighbours, as (row, col) deltas.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row-major. Positions are `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(rows: usize, cols: usize, cells: Vec<T>) -> Result<Self, String> {
+        if cells.len() != rows * cols {
+            return Err(format!(
+                "{} cells cannot fill a {rows}x{cols} grid",
+                cells.len()
+            ));
+        }
+        Ok(Self { cells, rows, cols })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+        let n_rows = rows.len();
+        let cols = rows.first().map_or(0, |r| r.len());
+        if let Some(r) = rows.iter().position(|r| r.len() != cols) {
+            return Err(format!(
+                "Row {r} has {} elements, expected {cols}",
+                rows[r].len()
+            ));
+        }
+        Ok(Self {
+            cells: rows.into_iter().flatten().collect(),
+            rows: n_rows,
+            cols,
+        })
+    }
+
+    /// Parses one row per line, mapping every char with `f`. See [`parse::grid`].
+    pub fn parse(input: &str, f: impl FnMut(char) -> Result<T, String>) -> ParseResult<Self> {
+        parse::grid(input, f)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn in_bounds(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.rows && (col as usize) < self.cols
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            Some(&mut self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Position reached by moving `delta` from `pos`, if it stays inside the grid.
+    pub fn offset(
+        &self,
+        (row, col): (usize, usize),
+        delta: (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let r = row as isize + delta.0;
+        let c = col as isize + delta.1;
+        self.in_bounds(r, c).then_some((r as usize, c as usize))
+    }
+
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |d| self.offset(pos, *d))
+    }
+
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |d| self.offset(pos, *d))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.cols);
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |r| (0..cols).map(move |c| (r, c)))
+    }
+
+    /// Iterates every cell together with its position.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    pub fn count(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        self.cells.iter().filter(|c| pred(c)).count()
+    }
+
+    pub fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, c)| pred(c)).map(|(p, _)| p)
+    }
+
+    /// Renders the grid back to text, one line per row.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut out = String::with_capacity(self.rows * (self.cols + 1));
+        for row in self.iter_rows() {
+            out.extend(row.iter().map(&mut f));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn rebuild(
+        &self,
+        rows: usize,
+        cols: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for r in 0..rows {
+            for c in 0..cols {
+                cells.push(self[source(r, c)].clone());
+            }
+        }
+        Self { cells, rows, cols }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn transpose(&self) -> Self {
+        self.rebuild(self.cols, self.rows, |r, c| (c, r))
+    }
+
+    /// Rotates 90 degrees clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        let rows = self.rows;
+        self.rebuild(self.cols, self.rows, |r, c| (rows - 1 - c, r))
+    }
+
+    /// Rotates 90 degrees counter clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        let cols = self.cols;
+        self.rebuild(self.cols, self.rows, |r, c| (c, cols - 1 - r))
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let cols = self.cols;
+        self.rebuild(self.rows, self.cols, |r, c| (r, cols - 1 - c))
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let rows = self.rows;
+        self.rebuild(self.rows, self.cols, |r, c| (rows - 1 - r, c))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(col < self.cols, "Column {col} out of bounds");
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(col < self.cols, "Column {col} out of bounds");
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "#..
+##.";
+
+    fn parse(input: &str) -> Grid<char> {
+        Grid::parse(input, Ok).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = parse(EXAMPLE);
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.cols(), 3);
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.get((2, 0)), None);
+        assert!(Grid::parse("##\n#", Ok).is_err());
+        assert!(
+            Grid::parse("#x", |c| if c == '#' {
+                Ok(())
+            } else {
+                Err(format!("{c}"))
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = parse(EXAMPLE);
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((0, 1)).count(), 5);
+        assert_eq!(
+            grid.neighbours8((1, 1)).filter(|p| grid[*p] == '#').count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = parse(EXAMPLE);
+        assert_eq!(grid.row(1), &['#', '#', '.']);
+        assert_eq!(grid.column(0).collect::<String>(), "##");
+        assert_eq!(grid.count(|c| *c == '#'), 3);
+        assert_eq!(grid.position(|c| *c == '.'), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = parse(EXAMPLE);
+        assert_eq!(grid.render(|c| *c), "#..\n##.\n");
+        assert_eq!(grid.transpose().render(|c| *c), "##\n.#\n..\n");
+        assert_eq!(grid.rotate_cw().render(|c| *c), "##\n#.\n..\n");
+        assert_eq!(grid.rotate_ccw().render(|c| *c), "..\n.#\n##\n");
+        assert_eq!(grid.flip_horizontal().render(|c| *c), "..#\n.##\n");
+        assert_eq!(grid.flip_vertical().render(|c| *c), "##.\n#..\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, StdinLock},
+    path::Path,
+};
+
+/// Anything that can hand out its lines one by one, without the line breaks.
+///
+/// Lines are borrowed only for the duration of the callback, so sources over
+/// readers keep a single buffer alive and use constant memory on any input size.
+pub trait LineSource {
+    /// Calls `f` with the 1-based number and the content of every line, stopping
+    /// at the first error.
+    fn try_for_each_line(self, f: impl FnMut(usize, &str) -> io::Result<()>) -> io::Result<()>;
+}
+
+impl LineSource for &str {
+    fn try_for_each_line(self, mut f: impl FnMut(usize, &str) -> io::Result<()>) -> io::Result<()> {
+        for (i, line) in self.lines().enumerate() {
+            f(i + 1, line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Line iterator over any [`BufRead`] reusing a single buffer.
+pub struct LineReader<R> {
+    reader: R,
+    buf: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            line_number: 0,
+        }
+    }
+
+    /// Next line without its line break, `None` at the end of the input.
+    pub fn next_line(&mut self) -> Option<io::Result<&str>> {
+        self.buf.clear();
+        match self.reader.read_line(&mut self.buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                Some(Ok(trim_line_break(&self.buf)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Number of the last line returned by [`Self::next_line`], 1-based
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<R: BufRead> LineSource for LineReader<R> {
+    fn try_for_each_line(
+        mut self,
+        mut f: impl FnMut(usize, &str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        loop {
+            self.buf.clear();
+            if self.reader.read_line(&mut self.buf)? == 0 {
+                return Ok(());
+            }
+            self.line_number += 1;
+            f(self.line_number, trim_line_break(&self.buf))?;
+        }
+    }
+}
+
+fn trim_line_break(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Lines of the standard input
+pub fn stdin() -> LineReader<StdinLock<'static>> {
+    LineReader::new(io::stdin().lock())
+}
+
+/// Lines of the file at `path`, read through a buffer.
+pub fn open(path: impl AsRef<Path>) -> io::Result<LineReader<BufReader<File>>> {
+    Ok(LineReader::new(BufReader::new(File::open(path)?)))
+}
+
+/// File mapped into memory, its lines are slices of the mapping with no copies.
+#[cfg(feature = "mmap")]
+pub struct MappedFile(memmap2::Mmap);
+
+#[cfg(feature = "mmap")]
+impl MappedFile {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read only, the puzzle inputs are not expected to
+        // be modified while being solved.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self(map))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl LineSource for &MappedFile {
+    fn try_for_each_line(self, mut f: impl FnMut(usize, &str) -> io::Result<()>) -> io::Result<()> {
+        let bytes = self.as_bytes();
+        let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        for (i, line) in bytes.split(|b| *b == b'\n').enumerate() {
+            let line = std::str::from_utf8(line).map_err(invalid_data)?;
+            f(i + 1, line.strip_suffix('\r').unwrap_or(line))?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a parsing error so it can travel through the io errors of a [`LineSource`].
+pub fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Only the days with a streaming solver check it against every source.
+    #[cfg(any(
+        feature = "day1",
+        feature = "day3",
+        feature = "day5",
+        feature = "day10",
+        feature = "day11"
+    ))]
+    mod sources {
+        use std::fmt::Debug;
+
+        use super::super::*;
+
+        /// Every kind of [`LineSource`] behind one type, so the days can be checked with a
+        /// single closure.
+        pub(crate) enum Source {
+            Str(String),
+            Reader(LineReader<BufReader<File>>),
+            Bytes(LineReader<io::Cursor<String>>),
+            #[cfg(feature = "mmap")]
+            Mapped(MappedFile),
+        }
+
+        impl LineSource for Source {
+            fn try_for_each_line(
+                self,
+                f: impl FnMut(usize, &str) -> io::Result<()>,
+            ) -> io::Result<()> {
+                match self {
+                    Self::Str(text) => text.as_str().try_for_each_line(f),
+                    Self::Reader(reader) => reader.try_for_each_line(f),
+                    Self::Bytes(reader) => reader.try_for_each_line(f),
+                    #[cfg(feature = "mmap")]
+                    Self::Mapped(file) => (&file).try_for_each_line(f),
+                }
+            }
+        }
+
+        /// `solve` gives the same answers from a `&str`, a [`LineReader`] and a
+        /// [`MappedFile`], for the example and for the input at `path`.
+        pub(crate) fn check_sources<T: PartialEq + Debug>(
+            solve: impl Fn(Source) -> io::Result<T>,
+            (example, example_answer): (&str, T),
+            (path, answer): (&str, T),
+        ) {
+            let text = std::fs::read_to_string(path).unwrap();
+            let sources = vec![
+                (Source::Str(example.to_string()), &example_answer),
+                (
+                    Source::Bytes(LineReader::new(io::Cursor::new(example.to_string()))),
+                    &example_answer,
+                ),
+                (Source::Str(text), &answer),
+                (Source::Reader(open(path).unwrap()), &answer),
+            ];
+            #[cfg(feature = "mmap")]
+            let sources = sources
+                .into_iter()
+                .chain([(Source::Mapped(MappedFile::open(path).unwrap()), &answer)]);
+
+            for (i, (source, expected)) in sources.into_iter().enumerate() {
+                assert_eq!(solve(source).unwrap(), *expected, "source {i} of {path}");
+            }
+        }
+    }
+    #[cfg(any(
+        feature = "day1",
+        feature = "day3",
+        feature = "day5",
+        feature = "day10",
+        feature = "day11"
+    ))]
+    pub(crate) use sources::check_sources;
+
+    fn collect(source: impl LineSource) -> Vec<(usize, String)> {
+        let mut lines = vec![];
+        source
+            .try_for_each_line(|n, l| {
+                lines.push((n, l.to_string()));
+                Ok(())
+            })
+            .unwrap();
+        lines
+    }
+
+    #[test]
+    fn test_line_reader() {
+        let expected = vec![
+            (1, "a".to_string()),
+            (2, "".to_string()),
+            (3, "bc".to_string()),
+        ];
+        assert_eq!(collect(LineReader::new("a\r\n\nbc\n".as_bytes())), expected);
+        assert_eq!(collect(LineReader::new("a\n\nbc".as_bytes())), expected);
+        assert_eq!(collect("a\n\nbc\n"), expected);
+
+        let mut reader = LineReader::new("x\ny".as_bytes());
+        assert_eq!(reader.next_line().unwrap().unwrap(), "x");
+        assert_eq!(reader.next_line().unwrap().unwrap(), "y");
+        assert_eq!(reader.line_number(), 2);
+        assert!(reader.next_line().is_none());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mapped_file() {
+        let file = MappedFile::open("./src/ed2025/day1/input.txt").unwrap();
+        let mapped = collect(&file);
+        let read = collect(open("./src/ed2025/day1/input.txt").unwrap());
+        assert_eq!(mapped, read);
+        assert!(!mapped.is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt::Debug;
+
+/// Integer types an [`IntervalSet`] can hold.
+pub trait Point: Copy + Ord + Debug {
+    fn checked_next(self) -> Option<Self>;
+    fn checked_prev(self) -> Option<Self>;
+
+    /// Number of points in `start..=end`, `start <= end`
+    fn span(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_point {
+    ($($t:ty),*) => {
+        $(
+            impl Point for $t {
+                fn checked_next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: Self, end: Self) -> u128 {
+                    (end as i128 - start as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_point!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Set of integers stored as sorted, disjoint and non adjacent `start..=end` intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: Point> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every point from `start` to `end`, both included
+    pub fn from_interval(start: T, end: T) -> Self {
+        let mut set = Self::new();
+        set.insert(start, end);
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The intervals, sorted and with gaps between them
+    pub fn intervals(&self) -> &[(T, T)] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    /// Number of points in the set
+    pub fn len(&self) -> u128 {
+        self.iter().map(|(start, end)| T::span(start, end)).sum()
+    }
+
+    /// Binary search for the interval holding `x`
+    pub fn find(&self, x: T) -> Option<(T, T)> {
+        let i = self.intervals.partition_point(|(_, end)| *end < x);
+        self.intervals
+            .get(i)
+            .copied()
+            .filter(|(start, _)| *start <= x)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.find(x).is_some()
+    }
+
+    /// First and last interval touching `start..=end`, adjacent ones included if
+    /// `adjacent`, as a range of indices.
+    fn touching(&self, start: T, end: T, adjacent: bool) -> std::ops::Range<usize> {
+        let (after_start, before_end) = match adjacent {
+            true => (start.checked_prev(), end.checked_next()),
+            false => (Some(start), Some(end)),
+        };
+        let first = self
+            .intervals
+            .partition_point(|(_, e)| after_start.is_some_and(|s| *e < s));
+        let last = self
+            .intervals
+            .partition_point(|(s, _)| before_end.is_none_or(|e| *s <= e));
+        first..last.max(first)
+    }
+
+    /// Adds `start..=end`, merging it with the intervals it overlaps or touches.
+    /// Panics if `end` is before `start`.
+    pub fn insert(&mut self, start: T, end: T) {
+        assert!(
+            start <= end,
+            "Interval ends at {end:?} before its start {start:?}"
+        );
+        let touching = self.touching(start, end, true);
+        let merged = match touching.is_empty() {
+            true => (start, end),
+            false => (
+                start.min(self.intervals[touching.start].0),
+                end.max(self.intervals[touching.end - 1].1),
+            ),
+        };
+        self.intervals.splice(touching, [merged]);
+    }
+
+    /// Takes `start..=end` out, splitting the intervals that go past it.
+    /// Panics if `end` is before `start`.
+    pub fn remove(&mut self, start: T, end: T) {
+        assert!(
+            start <= end,
+            "Interval ends at {end:?} before its start {start:?}"
+        );
+        let touching = self.touching(start, end, false);
+        if touching.is_empty() {
+            return;
+        }
+        let (first, _) = self.intervals[touching.start];
+        let (_, last) = self.intervals[touching.end - 1];
+        let left = start
+            .checked_prev()
+            .filter(|_| first < start)
+            .map(|e| (first, e));
+        let right = end.checked_next().filter(|_| last > end).map(|s| (s, last));
+        self.intervals
+            .splice(touching, left.into_iter().chain(right));
+    }
+
+    /// Intervals sorted by start joined in one pass with the ones they overlap or touch
+    fn coalesce(sorted: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut intervals: Vec<(T, T)> = vec![];
+        for (start, end) in sorted {
+            match intervals.last_mut() {
+                Some((_, last)) if last.checked_next().is_none_or(|next| start <= next) => {
+                    *last = (*last).max(end)
+                }
+                _ => intervals.push((start, end)),
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Adds every interval of `other`
+    pub fn merge(&mut self, other: &Self) {
+        *self = self.union(other);
+    }
+
+    /// Both lists swept together by start, in O(n + m)
+    pub fn union(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        Self::coalesce(std::iter::from_fn(|| match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if y.0 < x.0 => b.next(),
+            (Some(_), _) => a.next(),
+            (None, _) => b.next(),
+        }))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (a, b) = (&self.intervals, &other.intervals);
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = vec![];
+        while i < a.len() && j < b.len() {
+            let start = a[i].0.max(b[j].0);
+            let end = a[i].1.min(b[j].1);
+            if start <= end {
+                intervals.push((start, end));
+            }
+            if a[i].1 < b[j].1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        // Pieces of disjoint non adjacent intervals are still disjoint and non adjacent
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for (start, end) in other.iter() {
+            difference.remove(start, end);
+        }
+        difference
+    }
+
+    /// Points of `lo..=hi` not in the set
+    pub fn complement(&self, lo: T, hi: T) -> Self {
+        Self::from_interval(lo, hi).difference(self)
+    }
+
+    /// Intervals missing between the first and the last interval of the set
+    pub fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.intervals.windows(2).map(|pair| {
+            (
+                pair[0].1.checked_next().unwrap(),
+                pair[1].0.checked_prev().unwrap(),
+            )
+        })
+    }
+}
+
+/// Sorts the intervals by start and joins them in one pass, O(n log n).
+/// Panics if an interval ends before its start.
+impl<T: Point> FromIterator<(T, T)> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut intervals: Vec<(T, T)> = iter.into_iter().collect();
+        for (start, end) in &intervals {
+            assert!(
+                start <= end,
+                "Interval ends at {end:?} before its start {start:?}"
+            );
+        }
+        intervals.sort_unstable_by_key(|(start, _)| *start);
+        Self::coalesce(intervals)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Same set as a bitmap of every `u8`
+    fn bitmap(set: &IntervalSet<u8>) -> [bool; 256] {
+        let mut bits = [false; 256];
+        for (start, end) in set.iter() {
+            bits[start as usize..=end as usize].fill(true);
+        }
+        bits
+    }
+
+    fn check(set: &IntervalSet<u8>, expected: &[bool; 256]) {
+        assert_eq!(bitmap(set), *expected);
+        assert!(
+            set.intervals()
+                .windows(2)
+                .all(|w| w[0].1 as usize + 1 < w[1].0 as usize)
+        );
+        assert_eq!(set.len(), expected.iter().filter(|b| **b).count() as u128);
+        for x in 0..=255u8 {
+            assert_eq!(set.contains(x), expected[x as usize], "{x}");
+        }
+    }
+
+    #[test]
+    fn test_against_bitmap() {
+        let mut set = IntervalSet::new();
+        let mut expected = [false; 256];
+        let mut state = 17u32;
+        for step in 0..400 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let a = (state >> 8) as u8;
+            let b = a.saturating_add((state >> 20) as u8 % 24);
+            if step % 3 == 0 {
+                set.remove(a, b);
+                expected[a as usize..=b as usize].fill(false);
+            } else {
+                set.insert(a, b);
+                expected[a as usize..=b as usize].fill(true);
+            }
+            check(&set, &expected);
+        }
+
+        let other: IntervalSet<u8> = [(0, 10), (40, 90), (200, 255)].into_iter().collect();
+        let other_bits = bitmap(&other);
+        let combine = |f: fn(bool, bool) -> bool| {
+            let mut bits = [false; 256];
+            for x in 0..256 {
+                bits[x] = f(expected[x], other_bits[x]);
+            }
+            bits
+        };
+        check(&set.union(&other), &combine(|a, b| a || b));
+        check(&other.union(&set), &combine(|a, b| a || b));
+        let mut merged = other.clone();
+        merged.merge(&set);
+        check(&merged, &combine(|a, b| a || b));
+        let both = [set.intervals(), other.intervals()].concat();
+        let collected: IntervalSet<u8> = both.into_iter().rev().collect();
+        check(&collected, &combine(|a, b| a || b));
+        check(&set.intersection(&other), &combine(|a, b| a && b));
+        check(&set.difference(&other), &combine(|a, b| a && !b));
+        check(&set.complement(0, 255), &combine(|a, _| !a));
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut set: IntervalSet<i32> =
+            [(3, 5), (10, 14), (16, 20), (12, 18)].into_iter().collect();
+        assert_eq!(set.intervals(), &[(3, 5), (10, 20)]);
+        assert_eq!(set.len(), 14);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![(6, 9)]);
+        assert_eq!(set.find(15), Some((10, 20)));
+
+        set.insert(6, 9);
+        assert_eq!(set.intervals(), &[(3, 20)]);
+        set.remove(i32::MIN, 4);
+        set.insert(i32::MAX, i32::MAX);
+        assert_eq!(set.intervals(), &[(5, 20), (i32::MAX, i32::MAX)]);
+        assert_eq!(set.complement(0, 30).intervals(), &[(0, 4), (21, 30)]);
+
+        let full = IntervalSet::from_interval(u64::MIN, u64::MAX);
+        assert_eq!(full.len(), u64::MAX as u128 + 1);
+        assert!(full.complement(0, 100).is_empty());
+    }
+}
@@ -1 +1,22 @@
+pub mod bigint;
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day4",
+    feature = "day5",
+    feature = "day6",
+    feature = "day7",
+    feature = "day8",
+    feature = "day9",
+    feature = "day10",
+    feature = "day11",
+    feature = "day12"
+))]
 pub mod ed2025;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod parse;
@@ -0,0 +1,437 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::grid::Grid;
+
+/// Error with the 1-based line and column where parsing failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for String {
+    fn from(value: ParseError) -> Self {
+        value.to_string()
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Position over a str that the parsers below consume from left to right.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+    first_line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::at_line(input, 1)
+    }
+
+    /// Cursor over a piece of a bigger input that starts at `first_line`, so errors
+    /// report the line on the whole input.
+    pub fn at_line(input: &'a str, first_line: usize) -> Self {
+        Self {
+            input,
+            pos: 0,
+            first_line,
+        }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Line currently being parsed, 1-based
+    pub fn line(&self) -> usize {
+        self.first_line + self.input[..self.pos].matches('\n').count()
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        let before = &self.input[..pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: self.first_line + before.matches('\n').count(),
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Consumes `c` if it is next.
+    pub fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{c}'")))
+        }
+    }
+
+    pub fn tag(&mut self, tag: &str) -> ParseResult<()> {
+        if self.rest().starts_with(tag) {
+            self.pos += tag.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected \"{tag}\"")))
+        }
+    }
+
+    pub fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Skips spaces and tabs, but not line breaks.
+    pub fn skip_spaces(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// Run of non whitespace chars, at least one.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        let word = self.take_while(|c| !c.is_whitespace());
+        if word.is_empty() {
+            Err(self.error("Expected a word"))
+        } else {
+            Ok(word)
+        }
+    }
+
+    fn number<T: FromStr>(&mut self, start: usize) -> ParseResult<T> {
+        if self.take_while(|c| c.is_ascii_digit()).is_empty() {
+            self.pos = start;
+            return Err(self.error("Expected a number"));
+        }
+        self.input[start..self.pos]
+            .parse()
+            .map_err(|_| self.error_at(start, "Number out of range"))
+    }
+
+    /// Unsigned integer in base 10.
+    pub fn uint<T: FromStr>(&mut self) -> ParseResult<T> {
+        self.number(self.pos)
+    }
+
+    /// Integer in base 10 with an optional `+` or `-` sign.
+    pub fn int<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = self.pos;
+        if !self.eat('-') {
+            self.eat('+');
+        }
+        self.number(start)
+    }
+
+    pub fn digit(&mut self) -> ParseResult<u32> {
+        match self.peek().and_then(|c| c.to_digit(10)) {
+            Some(d) => {
+                self.pos += 1;
+                Ok(d)
+            }
+            None => Err(self.error("Expected a digit")),
+        }
+    }
+
+    /// Runs `f`, rewinding the cursor if it fails.
+    pub fn optional<T>(&mut self, f: impl FnOnce(&mut Self) -> ParseResult<T>) -> Option<T> {
+        let start = self.pos;
+        match f(self) {
+            Ok(v) => Some(v),
+            Err(_) => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// Applies `f` as many times as it succeeds, at least once.
+    pub fn many1<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut items = vec![f(self)?];
+        while let Some(item) = self.optional(&mut f) {
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// One or more `f` separated by `sep`.
+    pub fn sep_by<T>(
+        &mut self,
+        sep: char,
+        mut f: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut items = vec![f(self)?];
+        while self.eat(sep) {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    /// `f` between `open` and `close`.
+    pub fn delimited<T>(
+        &mut self,
+        open: char,
+        close: char,
+        f: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        self.expect(open)?;
+        let v = f(self)?;
+        self.expect(close)?;
+        Ok(v)
+    }
+
+    /// Consumes the line break that ends the current line, if any.
+    pub fn end_of_line(&mut self) -> ParseResult<()> {
+        self.eat('\r');
+        if self.eat('\n') || self.is_eof() {
+            Ok(())
+        } else {
+            Err(self.error("Expected end of line"))
+        }
+    }
+
+    /// Fails unless only whitespace is left.
+    pub fn finish(&mut self) -> ParseResult<()> {
+        self.take_while(char::is_whitespace);
+        if self.is_eof() {
+            Ok(())
+        } else {
+            Err(self.error(format!("Unexpected \"{}\"", self.rest())))
+        }
+    }
+
+    /// Rows of chars up to the next blank line or the end of the input, each char
+    /// mapped with `f`. Rows are not trimmed, a blank is a cell like any other char.
+    pub fn grid<T>(
+        &mut self,
+        mut f: impl FnMut(char) -> Result<T, String>,
+    ) -> ParseResult<Grid<T>> {
+        let mut rows: Vec<Vec<T>> = vec![];
+        while !self.is_eof() {
+            let line = self
+                .rest()
+                .split('\n')
+                .next()
+                .unwrap()
+                .trim_end_matches('\r');
+            if line.trim().is_empty() {
+                break;
+            }
+
+            let mut row = Vec::with_capacity(line.len());
+            for c in line.chars() {
+                row.push(f(c).map_err(|e| self.error(e))?);
+                self.pos += c.len_utf8();
+            }
+
+            if let Some(first) = rows.first()
+                && first.len() != row.len()
+            {
+                return Err(self.error(format!(
+                    "Row has {} elements, expected {}",
+                    row.len(),
+                    first.len()
+                )));
+            }
+            rows.push(row);
+            self.end_of_line()?;
+        }
+
+        if rows.is_empty() {
+            return Err(self.error("Expected a grid"));
+        }
+        Ok(Grid::from_rows(rows).expect("Rows were checked"))
+    }
+}
+
+/// Blank line separated sections, together with the line each of them starts at.
+pub fn sections(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut line = 1;
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        // Skip the blank lines before the section
+        loop {
+            let (first, tail) = rest.split_once('\n').unwrap_or((rest, ""));
+            if !first.trim().is_empty() {
+                break;
+            }
+            if tail.is_empty() {
+                return None;
+            }
+            line += 1;
+            rest = tail;
+        }
+
+        let start = line;
+        let mut len = 0;
+        for l in rest.split_inclusive('\n') {
+            if l.trim().is_empty() {
+                break;
+            }
+            len += l.len();
+            line += 1;
+        }
+        let (section, tail) = rest.split_at(len);
+        rest = tail;
+        Some((start, section.trim_end()))
+    })
+}
+
+/// Applies `f` to every non blank line, which must consume the whole line. `input`
+/// starts at line `first_line` of the file, 1 for a whole file or the start given by
+/// [`sections`].
+pub fn lines<'a, T>(
+    input: &'a str,
+    first_line: usize,
+    mut f: impl FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+) -> ParseResult<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| line(l, first_line + i, &mut f))
+        .collect()
+}
+
+/// Applies `f` to a single line, which must consume it whole. `line_number` only
+/// matters for the errors.
+pub fn line<'a, T>(
+    line: &'a str,
+    line_number: usize,
+    f: impl FnOnce(&mut Cursor<'a>) -> ParseResult<T>,
+) -> ParseResult<T> {
+    let mut cursor = Cursor::at_line(line, line_number);
+    let v = f(&mut cursor)?;
+    cursor.finish()?;
+    Ok(v)
+}
+
+/// Grid of the whole input, see [`Cursor::grid`].
+pub fn grid<T>(input: &str, f: impl FnMut(char) -> Result<T, String>) -> ParseResult<Grid<T>> {
+    let mut cursor = Cursor::new(input);
+    cursor.take_while(char::is_whitespace);
+    // Do not eat the indentation of the first row
+    cursor.pos = input[..cursor.pos].rfind('\n').map_or(0, |i| i + 1);
+    let grid = cursor.grid(f)?;
+    cursor.finish()?;
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let mut cursor = Cursor::new("12,-3,+4,x");
+        assert_eq!(
+            cursor.sep_by(',', |c| c.int::<i32>()),
+            Err(ParseError {
+                line: 1,
+                column: 10,
+                message: "Expected a number".to_string()
+            })
+        );
+
+        let mut cursor = Cursor::new("12-300");
+        assert_eq!(cursor.uint::<u8>(), Ok(12));
+        cursor.expect('-').unwrap();
+        assert_eq!(cursor.uint::<u8>().unwrap_err().column, 4);
+    }
+
+    #[test]
+    fn test_delimited() {
+        let mut cursor = Cursor::new("[.#] (1,3) {7}");
+        let lights = cursor
+            .delimited('[', ']', |c| Ok(c.take_while(|c| c != ']')))
+            .unwrap();
+        assert_eq!(lights, ".#");
+        cursor.skip_spaces();
+        let button = cursor.delimited('(', ')', |c| c.sep_by(',', |c| c.uint::<usize>()));
+        assert_eq!(button, Ok(vec![1, 3]));
+        cursor.skip_spaces();
+        assert_eq!(
+            cursor
+                .delimited('(', ')', |c| c.uint::<usize>())
+                .unwrap_err()
+                .column,
+            12
+        );
+        assert_eq!(cursor.optional(|c| c.tag("{7}")), Some(()));
+        assert!(cursor.finish().is_ok());
+    }
+
+    #[test]
+    fn test_sections_and_lines() {
+        let input = "1-2\n3-4\n\n\n5\nx\n";
+        let sections: Vec<_> = sections(input).collect();
+        assert_eq!(sections, [(1, "1-2\n3-4"), (5, "5\nx")]);
+
+        let (start, ids) = sections[1];
+        let err = lines(ids, start, |c| c.uint::<usize>()).unwrap_err();
+        assert_eq!(err.line, 6);
+        assert_eq!(lines("1\n\n2", 1, |c| c.uint::<u8>()), Ok(vec![1, 2]));
+        let mut cursor = Cursor::at_line(ids, start);
+        cursor.uint::<usize>().unwrap();
+        cursor.end_of_line().unwrap();
+        assert_eq!(
+            cursor.uint::<usize>().unwrap_err().to_string(),
+            "6:1: Expected a number"
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let f = |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(format!("Invalid char {c}")),
+        };
+        let grid = grid("\n#.\n.#\n", f).unwrap();
+        assert_eq!(grid.rows(), 2);
+
+        let err = super::grid("#.\n.x", f).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = super::grid("#.\n.", f).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}